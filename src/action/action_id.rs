@@ -31,14 +31,45 @@ impl ActionId {
     #[allow(unused)]
     #[inline(always)]
     pub(crate) fn next(&self, time_passed: u64) -> Self {
-        Self(self.0 + time_passed.max(1))
+        Self(
+            self.0
+                .checked_add(time_passed.max(1))
+                .expect("action id overflow"),
+        )
     }
 
+    /// Duration since the `other` action.
+    ///
+    /// `other` is expected to precede this action, panics in debug builds
+    /// and returns zero in release builds otherwise. See
+    /// [`ActionId::checked_duration_since`] and
+    /// [`ActionId::saturating_duration_since`].
     pub fn duration_since(&self, other: ActionId) -> Duration {
-        let d = self.0.checked_sub(other.0);
+        let d = self.checked_duration_since(other);
         debug_assert!(d.is_some());
         d.unwrap_or(Duration::ZERO)
     }
+
+    /// Duration since the `other` action, `None` if it's later than this one.
+    ///
+    /// ```
+    /// use redux::ActionId;
+    /// use std::time::Duration;
+    ///
+    /// let first = ActionId::new_unchecked(0);
+    /// let last = ActionId::new_unchecked(u64::MAX);
+    ///
+    /// assert_eq!(last.checked_duration_since(first), Some(Duration::from_nanos(u64::MAX)));
+    /// assert_eq!(first.checked_duration_since(last), None);
+    /// ```
+    pub fn checked_duration_since(&self, other: ActionId) -> Option<Duration> {
+        self.0.checked_sub(other.0)
+    }
+
+    /// Duration since the `other` action, zero if it's later than this one.
+    pub fn saturating_duration_since(&self, other: ActionId) -> Duration {
+        self.checked_duration_since(other).unwrap_or_default()
+    }
 }
 
 impl From<ActionId> for Timestamp {
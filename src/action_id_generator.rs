@@ -0,0 +1,232 @@
+use crate::{ActionId, HybridLogicalClock, Timestamp, VirtualClock};
+
+/// Strategy generating ids of dispatched actions, see
+/// [`StoreBuilder::with_action_ids`](crate::StoreBuilder::with_action_ids).
+pub trait ActionIdGenerator {
+    /// Id preceding the first dispatched action, unless set with
+    /// [`StoreBuilder::initial_action_id`](crate::StoreBuilder::initial_action_id).
+    ///
+    /// `initial_time` is the time at which the store was created.
+    fn initial_id(&mut self, initial_time: Timestamp) -> ActionId {
+        ActionId::new_unchecked(initial_time.into())
+    }
+
+    /// Id of the next dispatched action, which must be greater than `prev`.
+    ///
+    /// `now` is the current time, from the monotonic time of the service.
+    fn next_id(&mut self, prev: ActionId, now: Timestamp) -> ActionId;
+
+    /// Advances the generator past an id received from another node, see
+    /// [`Store::observe_remote`](crate::Store::observe_remote).
+    fn observe_remote(&mut self, _remote: ActionId) {}
+}
+
+/// Ids are the current time, increased by 1 if needed to stay unique.
+///
+/// Default strategy of the [`Store`](crate::Store).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WallClockIds;
+
+impl ActionIdGenerator for WallClockIds {
+    fn next_id(&mut self, prev: ActionId, now: Timestamp) -> ActionId {
+        ActionId::new_unchecked((Timestamp::from(prev) + 1).max(now).into())
+    }
+}
+
+/// Ids are a sequence starting at 1, independent of time.
+///
+/// For fully deterministic ids in tests, e.g. when replaying actions or
+/// comparing them to golden files. Note that action times, taken from
+/// ids, are then meaningless.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SequenceIds;
+
+impl ActionIdGenerator for SequenceIds {
+    fn initial_id(&mut self, _initial_time: Timestamp) -> ActionId {
+        ActionId::ZERO
+    }
+
+    fn next_id(&mut self, prev: ActionId, _now: Timestamp) -> ActionId {
+        ActionId::new_unchecked(u64::from(prev) + 1)
+    }
+}
+
+/// Ids are the time of a [`VirtualClock`], from the given start, increased
+/// by 1 if needed to stay unique.
+///
+/// Unlike [`WallClockIds`], ids don't depend on the system time at which
+/// the store was created.
+#[derive(Debug, Clone)]
+pub struct VirtualClockIds {
+    clock: VirtualClock,
+    start: Timestamp,
+}
+
+impl VirtualClockIds {
+    pub fn new(clock: VirtualClock, start: Timestamp) -> Self {
+        Self { clock, start }
+    }
+
+    pub fn clock(&self) -> &VirtualClock {
+        &self.clock
+    }
+}
+
+impl ActionIdGenerator for VirtualClockIds {
+    fn initial_id(&mut self, _initial_time: Timestamp) -> ActionId {
+        ActionId::new_unchecked(self.start.into())
+    }
+
+    fn next_id(&mut self, prev: ActionId, _now: Timestamp) -> ActionId {
+        let now = self.start + self.clock.elapsed();
+        ActionId::new_unchecked((Timestamp::from(prev) + 1).max(now).into())
+    }
+}
+
+impl ActionIdGenerator for HybridLogicalClock {
+    /// Physical time of `initial_time`, with logical counter 0 and the id of
+    /// this node, unless the clock is already past it.
+    fn initial_id(&mut self, initial_time: Timestamp) -> ActionId {
+        let physical =
+            HybridLogicalClock::physical_time_of(ActionId::new_unchecked(initial_time.into()));
+        self.observe(ActionId::new_unchecked(physical.into()));
+        self.last()
+    }
+
+    fn next_id(&mut self, prev: ActionId, now: Timestamp) -> ActionId {
+        self.observe(prev);
+        self.tick(now)
+    }
+
+    fn observe_remote(&mut self, remote: ActionId) {
+        self.observe(remote);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{ActionWithMeta, Dispatcher, EnablingCondition, Store, StoreBuilder, SystemTime};
+
+    struct Action;
+
+    impl EnablingCondition<Vec<ActionId>> for Action {}
+
+    /// Records ids of dispatched actions.
+    fn reducer(
+        state: &mut Vec<ActionId>,
+        action: &ActionWithMeta<Action>,
+        _: &mut Dispatcher<Action, Vec<ActionId>>,
+    ) {
+        state.push(action.id());
+    }
+
+    fn effects<Ids>(
+        _: &mut Store<Vec<ActionId>, VirtualClock, Action, Ids>,
+        _: ActionWithMeta<Action>,
+    ) {
+    }
+
+    fn store<Ids: ActionIdGenerator>(
+        clock: &VirtualClock,
+        action_ids: Ids,
+    ) -> Store<Vec<ActionId>, VirtualClock, Action, Ids> {
+        StoreBuilder::with_action_ids(
+            reducer,
+            effects::<Ids>,
+            clock.clone(),
+            Vec::new(),
+            action_ids,
+        )
+        .initial_time(SystemTime::UNIX_EPOCH + Duration::from_nanos(1_000_000_123_456))
+        .initial_monotonic_time(clock.now())
+        .build()
+        .unwrap()
+    }
+
+    #[test]
+    fn hybrid_logical_clock() {
+        let clock = VirtualClock::new();
+        let mut store = store(&clock, HybridLogicalClock::new(5));
+        let initial = store.last_action_id();
+        assert_eq!(HybridLogicalClock::node_id_of(initial), 5);
+        assert_eq!(HybridLogicalClock::logical_of(initial), 0);
+        assert_eq!(
+            HybridLogicalClock::physical_time_of(initial),
+            HybridLogicalClock::physical_time_of(ActionId::new_unchecked(1_000_000_123_456))
+        );
+
+        for _ in 0..3 {
+            store.dispatch(Action);
+        }
+        clock.advance(Duration::from_secs(1));
+        store.dispatch(Action);
+
+        let ids = store.state().clone();
+        assert!(ids.windows(2).all(|ids| ids[0] < ids[1]));
+        assert!(ids
+            .iter()
+            .all(|id| HybridLogicalClock::node_id_of(*id) == 5));
+        assert_eq!(
+            ids.iter()
+                .map(|id| HybridLogicalClock::logical_of(*id))
+                .collect::<Vec<_>>(),
+            [1, 2, 3, 0]
+        );
+
+        // Clock of the remote node is ahead.
+        let remote = HybridLogicalClock::new(7).tick(Timestamp::new(2_000_000_000_000));
+        store.observe_remote(remote);
+        store.dispatch(Action);
+        let id = store.last_action_id();
+        assert!(id > remote);
+        assert_eq!(HybridLogicalClock::node_id_of(id), 5);
+        assert_eq!(HybridLogicalClock::logical_of(id), 1);
+    }
+
+    #[test]
+    fn sequence_ids() {
+        let ids = |initial_secs| {
+            let clock = VirtualClock::new();
+            let mut store = StoreBuilder::with_action_ids(
+                reducer,
+                effects,
+                clock.clone(),
+                Vec::new(),
+                SequenceIds,
+            )
+            .initial_time(SystemTime::UNIX_EPOCH + Duration::from_secs(initial_secs))
+            .build()
+            .unwrap();
+            assert_eq!(store.last_action_id(), ActionId::ZERO);
+            store.dispatch(Action);
+            clock.advance(Duration::from_secs(1));
+            store.dispatch(Action);
+            store.dispatch(Action);
+            store.state().clone()
+        };
+
+        let expected = [1, 2, 3].map(ActionId::new_unchecked);
+        assert_eq!(ids(100), expected);
+        assert_eq!(ids(200), expected);
+    }
+
+    #[test]
+    fn virtual_clock_ids() {
+        let clock = VirtualClock::new();
+        let action_ids = VirtualClockIds::new(clock.clone(), Timestamp::new(1_000));
+        let mut store = store(&clock, action_ids);
+        assert_eq!(store.last_action_id(), ActionId::new_unchecked(1_000));
+
+        store.dispatch(Action);
+        clock.advance(Duration::from_millis(5));
+        store.dispatch(Action);
+        store.dispatch(Action);
+        assert_eq!(
+            store.state(),
+            &[1_001, 5_001_000, 5_001_001].map(ActionId::new_unchecked)
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use std::time::Duration;
+
+use crate::{EnablingCondition, Invariant, Store, TimeService, VirtualClock};
+
+/// Step of an action sequence, run by [`SequenceRunner`].
+#[cfg_attr(feature = "fuzzing", derive(fuzzcheck::DefaultMutator))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Step<Action> {
+    /// Dispatch the action. Disabled actions are skipped.
+    Dispatch(Action),
+    /// Advance the clock by the given number of milliseconds.
+    AdvanceTime(u32),
+}
+
+/// Invariant violated while running an action sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequenceFailure {
+    /// Index of the step after which the invariant was violated,
+    /// `None` if it was violated by the initial state.
+    pub step: Option<usize>,
+    pub invariant: &'static str,
+}
+
+impl std::fmt::Display for SequenceFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.step {
+            Some(step) => write!(
+                f,
+                "invariant `{}` violated after step {step}",
+                self.invariant
+            ),
+            None => write!(
+                f,
+                "invariant `{}` violated by initial state",
+                self.invariant
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SequenceFailure {}
+
+/// Runs sequences of actions and time advances against a fresh store,
+/// checking invariants after every step.
+///
+/// Used by the fuzzing and property-based testing harnesses, but can be
+/// used directly, e.g. to replay a reproducer.
+pub struct SequenceRunner<State, Service, Action> {
+    make_store: fn(VirtualClock) -> Store<State, Service, Action>,
+    invariants: Vec<(&'static str, Invariant<State>)>,
+}
+
+impl<State, Service, Action> Clone for SequenceRunner<State, Service, Action> {
+    fn clone(&self) -> Self {
+        Self {
+            make_store: self.make_store,
+            invariants: self.invariants.clone(),
+        }
+    }
+}
+
+impl<State, Service, Action> SequenceRunner<State, Service, Action>
+where
+    Service: TimeService,
+    Action: EnablingCondition<State> + Clone,
+{
+    /// `make_store` creates the initial store for each run. Its service
+    /// must take time from the given clock, so that
+    /// [`Step::AdvanceTime`] has an effect.
+    pub fn new(make_store: fn(VirtualClock) -> Store<State, Service, Action>) -> Self {
+        Self {
+            make_store,
+            invariants: Vec::new(),
+        }
+    }
+
+    /// Adds an invariant, which must hold after every step.
+    pub fn invariant(mut self, name: &'static str, invariant: Invariant<State>) -> Self {
+        self.invariants.push((name, invariant));
+        self
+    }
+
+    /// Runs the steps, returning the resulting store.
+    ///
+    /// Panics in reducers or effects are not caught.
+    pub fn run(
+        &self,
+        steps: &[Step<Action>],
+    ) -> Result<Store<State, Service, Action>, SequenceFailure> {
+        let clock = VirtualClock::new();
+        let mut store = (self.make_store)(clock.clone());
+        self.check(store.state(), None)?;
+
+        for (i, step) in steps.iter().enumerate() {
+            match step {
+                Step::Dispatch(action) => {
+                    store.dispatch(action.clone());
+                }
+                Step::AdvanceTime(millis) => {
+                    clock.advance(Duration::from_millis(*millis as u64));
+                }
+            }
+            self.check(store.state(), Some(i))?;
+        }
+
+        Ok(store)
+    }
+
+    fn check(&self, state: &State, step: Option<usize>) -> Result<(), SequenceFailure> {
+        match self.invariants.iter().find(|(_, check)| !check(state)) {
+            Some(&(invariant, _)) => Err(SequenceFailure { step, invariant }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ActionWithMeta, Dispatcher};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Add(u32);
+
+    impl EnablingCondition<u32> for Add {}
+
+    fn reducer(state: &mut u32, action: &ActionWithMeta<Add>, _: &mut Dispatcher<Add, u32>) {
+        *state += action.action().0;
+    }
+
+    fn effects(_: &mut Store<u32, VirtualClock, Add>, _: ActionWithMeta<Add>) {}
+
+    fn runner(
+        make_store: fn(VirtualClock) -> Store<u32, VirtualClock, Add>,
+    ) -> SequenceRunner<u32, VirtualClock, Add> {
+        SequenceRunner::new(make_store).invariant("below 10", |state| *state < 10)
+    }
+
+    fn store(clock: VirtualClock) -> Store<u32, VirtualClock, Add> {
+        Store::builder(reducer, effects, clock, 0).build().unwrap()
+    }
+
+    #[test]
+    fn run() {
+        let store = runner(store)
+            .run(&[
+                Step::Dispatch(Add(3)),
+                Step::AdvanceTime(1500),
+                Step::Dispatch(Add(4)),
+                Step::AdvanceTime(500),
+            ])
+            .unwrap();
+        assert_eq!(store.state(), &7);
+        assert_eq!(store.service.elapsed(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn failing_step() {
+        let failure = runner(store)
+            .run(&[
+                Step::Dispatch(Add(3)),
+                Step::AdvanceTime(5),
+                Step::Dispatch(Add(6)),
+                Step::Dispatch(Add(1)),
+                Step::Dispatch(Add(2)),
+            ])
+            .map(drop)
+            .unwrap_err();
+        assert_eq!(
+            failure,
+            SequenceFailure {
+                step: Some(3),
+                invariant: "below 10",
+            }
+        );
+        assert_eq!(
+            failure.to_string(),
+            "invariant `below 10` violated after step 3"
+        );
+    }
+
+    #[test]
+    fn failing_initial_state() {
+        fn store(clock: VirtualClock) -> Store<u32, VirtualClock, Add> {
+            Store::builder(reducer, effects, clock, 10).build().unwrap()
+        }
+
+        let failure = runner(store).run(&[]).map(drop).unwrap_err();
+        assert_eq!(failure.step, None);
+        assert_eq!(
+            failure.to_string(),
+            "invariant `below 10` violated by initial state"
+        );
+    }
+}
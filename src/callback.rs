@@ -11,42 +11,134 @@ pub struct AnyAction(pub Box<dyn std::any::Any>);
 #[distributed_slice]
 pub static CALLBACKS: [(&str, fn(&str, Box<dyn std::any::Any>) -> AnyAction)];
 
-#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
-pub struct Callback<T> {
+/// Same as [`CALLBACKS`], but for callbacks returning multiple actions.
+#[cfg(feature = "serializable_callbacks")]
+#[distributed_slice]
+pub static MULTIPLE_CALLBACKS: [(&str, fn(&str, Box<dyn std::any::Any>) -> Vec<AnyAction>)];
+
+/// Callback which converts its argument into an action.
+///
+/// `A` is the type returned by the callback function. By default it's
+/// the type-erased [`AnyAction`], which the user's action type is
+/// converted from by downcasting. Callbacks created with
+/// [`typed_callback!`](crate::typed_callback) carry the concrete action
+/// type instead, so no downcasting is needed to call them. Only when such
+/// callback gets deserialized, it falls back to the type-erased path.
+///
+/// Callbacks returning multiple actions are [`MultipleCallback`]s.
+#[derive(Serialize, Deserialize)]
+pub struct Callback<T, A = AnyAction> {
     #[serde(skip, default = "default_fun_ptr")]
-    fun_ptr: Option<fn(T) -> AnyAction>,
+    fun_ptr: Option<fn(T) -> A>,
     pub fun_name: Cow<'static, str>,
 }
 
-fn default_fun_ptr<T>() -> Option<fn(T) -> AnyAction> {
+fn default_fun_ptr<F>() -> Option<F> {
     None
 }
 
-impl<T: 'static> Callback<T> {
-    pub fn new(name: &'static str, fun_ptr: fn(T) -> AnyAction) -> Self {
+impl<T: 'static, A: 'static> Callback<T, A> {
+    pub fn new(name: &'static str, fun_ptr: fn(T) -> A) -> Self {
         Self {
             fun_ptr: Some(fun_ptr),
             fun_name: Cow::Borrowed(name),
         }
     }
 
+    /// Calls the callback and converts its result into an action.
     pub fn call<Action>(&self, args: T) -> Action
     where
-        Action: From<AnyAction>,
+        A: Into<Action>,
     {
         if let Some(fun) = self.fun_ptr {
             return fun(args).into();
         }
 
         #[cfg(not(feature = "serializable_callbacks"))]
-        unimplemented!();
+        panic!(
+            "callback function {} was deserialized without the `serializable_callbacks` feature",
+            self.fun_name
+        );
 
         #[cfg(feature = "serializable_callbacks")]
         {
             // We reach this point only when the callback was deserialized
             for (name, fun) in CALLBACKS {
                 if name == &self.fun_name {
-                    return fun(std::any::type_name::<T>(), Box::new(args)).into();
+                    let action = fun(std::any::type_name::<T>(), Box::new(args));
+                    return from_any_action::<A>(action).into();
+                }
+            }
+
+            panic!("callback function {} not found", self.fun_name)
+        }
+    }
+}
+
+/// Callback which converts its argument into a sequence of actions,
+/// created with `-> [Action]` in [`callback!`](crate::callback).
+///
+/// Same as [`Callback`] otherwise, but a distinct type, so it can only be
+/// passed where multiple actions are expected, e.g. to
+/// [`Store::dispatch_multiple_callback`](crate::Store::dispatch_multiple_callback).
+///
+/// ```
+/// use redux::{callback, MultipleCallback};
+///
+/// let numbers: MultipleCallback<u32> = callback!(numbers(n: u32) -> [u32] { 0..n });
+/// let numbers: Vec<u32> = numbers
+///     .call::<redux::AnyAction>(3)
+///     .into_iter()
+///     .map(|action| *action.0.downcast::<u32>().unwrap())
+///     .collect();
+/// assert_eq!(numbers, [0, 1, 2]);
+/// ```
+///
+/// ```compile_fail
+/// use redux::{callback, Callback};
+///
+/// let numbers: Callback<u32> = callback!(numbers(n: u32) -> [u32] { 0..n });
+/// ```
+#[derive(Serialize, Deserialize)]
+pub struct MultipleCallback<T, A = AnyAction> {
+    #[serde(skip, default = "default_fun_ptr")]
+    fun_ptr: Option<fn(T) -> Vec<A>>,
+    pub fun_name: Cow<'static, str>,
+}
+
+impl<T: 'static, A: 'static> MultipleCallback<T, A> {
+    pub fn new(name: &'static str, fun_ptr: fn(T) -> Vec<A>) -> Self {
+        Self {
+            fun_ptr: Some(fun_ptr),
+            fun_name: Cow::Borrowed(name),
+        }
+    }
+
+    /// Calls the callback and converts its result into actions, in the
+    /// order they were returned by the callback.
+    pub fn call<Action>(&self, args: T) -> Vec<Action>
+    where
+        A: Into<Action>,
+    {
+        if let Some(fun) = self.fun_ptr {
+            return fun(args).into_iter().map(Into::into).collect();
+        }
+
+        #[cfg(not(feature = "serializable_callbacks"))]
+        panic!(
+            "callback function {} was deserialized without the `serializable_callbacks` feature",
+            self.fun_name
+        );
+
+        #[cfg(feature = "serializable_callbacks")]
+        {
+            // We reach this point only when the callback was deserialized
+            for (name, fun) in MULTIPLE_CALLBACKS {
+                if name == &self.fun_name {
+                    return fun(std::any::type_name::<T>(), Box::new(args))
+                        .into_iter()
+                        .map(|action| from_any_action::<A>(action).into())
+                        .collect();
                 }
             }
 
@@ -55,26 +147,114 @@ impl<T: 'static> Callback<T> {
     }
 }
 
+/// Implements `Clone`, `PartialEq`, `Eq` and `Debug` for a callback type,
+/// without requiring them for `T` and `A`.
+macro_rules! impl_callback_traits {
+    ($callback_ty:ident) => {
+        impl<T, A> Clone for $callback_ty<T, A> {
+            fn clone(&self) -> Self {
+                Self {
+                    fun_ptr: self.fun_ptr,
+                    fun_name: self.fun_name.clone(),
+                }
+            }
+        }
+
+        impl<T, A> PartialEq for $callback_ty<T, A> {
+            fn eq(&self, other: &Self) -> bool {
+                self.fun_ptr.map(|fun| fun as usize) == other.fun_ptr.map(|fun| fun as usize)
+                    && self.fun_name == other.fun_name
+            }
+        }
+
+        impl<T, A> Eq for $callback_ty<T, A> {}
+
+        impl<T, A> std::fmt::Debug for $callback_ty<T, A> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(stringify!($callback_ty))
+                    .field("fun_ptr", &self.fun_ptr.map(|fun| fun as usize))
+                    .field("fun_name", &self.fun_name)
+                    .finish()
+            }
+        }
+    };
+}
+
+impl_callback_traits!(Callback);
+impl_callback_traits!(MultipleCallback);
+
+/// Converts the output of a deserialized callback into `A`.
+///
+/// Returns `action` itself if `A` is [`AnyAction`], otherwise downcasts
+/// the wrapped value into `A`.
+#[cfg(feature = "serializable_callbacks")]
+fn from_any_action<A: 'static>(action: AnyAction) -> A {
+    let action: Box<dyn std::any::Any> = Box::new(action);
+    match action.downcast::<A>() {
+        Ok(action) => *action,
+        Err(action) => {
+            let AnyAction(action) = *action
+                .downcast::<AnyAction>()
+                .unwrap_or_else(|_| unreachable!());
+            *action.downcast::<A>().unwrap_or_else(|_| {
+                panic!(
+                    "Invalid callback output type, expected: {}",
+                    std::any::type_name::<A>()
+                )
+            })
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! _callback {
-    ($callback_name:ident, $action_ty:ty, $arg:tt, $arg_type:ty, $body:expr) => {{
-        use $crate::{AnyAction, Callback};
+    ($callback_name:ident, $action_ty:ty, $arg:tt, $arg_type:ty, $body:expr) => {
+        $crate::_callback!(@impl Callback, CALLBACKS, $callback_name, AnyAction, AnyAction, $arg, $arg_type, {
+            let action: $action_ty = ($body).into();
+            AnyAction(Box::new(action))
+        }, |action| action)
+    };
+    (@typed $callback_name:ident, $action_ty:ty, $arg:tt, $arg_type:ty, $body:expr) => {
+        $crate::_callback!(@impl Callback, CALLBACKS, $callback_name, $action_ty, AnyAction, $arg, $arg_type, {
+            ($body).into()
+        }, |action| AnyAction(Box::new(action)))
+    };
+    (@multiple $callback_name:ident, $action_ty:ty, $arg:tt, $arg_type:ty, $body:expr) => {
+        $crate::_callback!(@impl MultipleCallback, MULTIPLE_CALLBACKS, $callback_name, Vec<AnyAction>, Vec<AnyAction>, $arg, $arg_type, {
+            ($body)
+                .into_iter()
+                .map(|action| {
+                    let action: $action_ty = action.into();
+                    AnyAction(Box::new(action))
+                })
+                .collect()
+        }, |actions| actions)
+    };
+    (@multiple_typed $callback_name:ident, $action_ty:ty, $arg:tt, $arg_type:ty, $body:expr) => {
+        $crate::_callback!(@impl MultipleCallback, MULTIPLE_CALLBACKS, $callback_name, Vec<$action_ty>, Vec<AnyAction>, $arg, $arg_type, {
+            ($body).into_iter().map(Into::into).collect()
+        }, |actions| actions.into_iter().map(|action| AnyAction(Box::new(action))).collect())
+    };
+    // `$out_ty` is returned by the callback function, `$erased_ty` by the
+    // function registered in `$slice`, converted from `$out_ty` by `$erase`.
+    (@impl $callback_ty:ident, $slice:ident, $callback_name:ident, $out_ty:ty, $erased_ty:ty, $arg:tt, $arg_type:ty, $body:expr, $erase:expr) => {{
+        use $crate::{AnyAction, $callback_ty};
 
         #[cfg(feature = "serializable_callbacks")]
-        use {$crate::CALLBACKS, linkme::distributed_slice};
+        use {$crate::$slice, linkme::distributed_slice};
 
-        redux::paste::paste! {
+        $crate::paste::paste! {
             #[allow(unused)] // $arg is marked as unused, but it's used in `$body`
-            fn convert_impl($arg: $arg_type) -> AnyAction {
-                let action: $action_ty = ($body).into();
-                AnyAction(Box::new(action))
+            fn convert_impl($arg: $arg_type) -> $out_ty {
+                $body
             }
 
-            fn $callback_name(call_type: &str, args: Box<dyn std::any::Any>) -> AnyAction {
+            #[allow(dead_code)] // Only called when deserialized with `serializable_callbacks`
+            fn $callback_name(call_type: &str, args: Box<dyn std::any::Any>) -> $erased_ty {
                 #[cfg(feature = "serializable_callbacks")]
                 {
-                    #[distributed_slice(CALLBACKS)]
-                    static CALLBACK_DESERIALIZE: (&str, fn(&str, Box<dyn std::any::Any>) -> AnyAction) = (
+                    #[distributed_slice($slice)]
+                    static CALLBACK_DESERIALIZE: (&str, fn(&str, Box<dyn std::any::Any>) -> $erased_ty) = (
                         stringify!($callback_name),
                         $callback_name,
                     );
@@ -86,11 +266,12 @@ macro_rules! _callback {
                         call_type,
                         stringify!($arg_type)));
 
-                convert_impl($arg)
+                let erase: fn($out_ty) -> $erased_ty = $erase;
+                erase(convert_impl($arg))
             }
         }
 
-        Callback::new(stringify!($callback_name), convert_impl)
+        $callback_ty::new(stringify!($callback_name), convert_impl)
     }};
 }
 
@@ -109,8 +290,24 @@ macro_rules! _callback {
 ///     MultipleArgumentsAction { value: arg1 + arg2 }
 /// })
 /// ```
+///
+/// Callbacks returning multiple actions use `-> [Action]` and a body
+/// evaluating to an iterator of actions, and create a [`MultipleCallback`].
+/// Actions are dispatched in the order they are returned.
+///
+/// ```ignore
+/// callback!(multiple_actions_callback(results: Vec<String>) -> [Action] {
+///     results.into_iter().map(|result| SomeAction { result })
+/// })
+/// ```
 #[macro_export]
 macro_rules! callback {
+    ($callback_name:ident(($($var:ident : $typ:ty),+)) -> [$action_ty:ty] $body:block) => {
+        $crate::_callback!(@multiple $callback_name, $action_ty, ($($var),+), ($($typ),+), $body)
+    };
+    ($callback_name:ident($var:ident : $typ:ty) -> [$action_ty:ty] $body:block) => {
+        $crate::_callback!(@multiple $callback_name, $action_ty, $var, $typ, $body)
+    };
     ($callback_name:ident(($($var:ident : $typ:ty),+)) -> $action_ty:ty $body:block) => {
         $crate::_callback!($callback_name, $action_ty, ($($var),+), ($($typ),+), $body)
     };
@@ -118,3 +315,31 @@ macro_rules! callback {
         $crate::_callback!($callback_name, $action_ty, $var, $typ, $body)
     };
 }
+
+/// Same as [`callback!`], but creates a [`Callback`] typed with the
+/// concrete action type, e.g. `Callback<String, Action>`, so calling it
+/// doesn't go through [`AnyAction`] and downcasting. Likewise, `-> [Action]`
+/// creates a `MultipleCallback<String, Action>`.
+///
+/// # Example
+///
+/// ```ignore
+/// let callback: Callback<String, Action> = typed_callback!(task_done_callback(result: String) -> Action {
+///     SomeAction { result }
+/// });
+/// ```
+#[macro_export]
+macro_rules! typed_callback {
+    ($callback_name:ident(($($var:ident : $typ:ty),+)) -> [$action_ty:ty] $body:block) => {
+        $crate::_callback!(@multiple_typed $callback_name, $action_ty, ($($var),+), ($($typ),+), $body)
+    };
+    ($callback_name:ident($var:ident : $typ:ty) -> [$action_ty:ty] $body:block) => {
+        $crate::_callback!(@multiple_typed $callback_name, $action_ty, $var, $typ, $body)
+    };
+    ($callback_name:ident(($($var:ident : $typ:ty),+)) -> $action_ty:ty $body:block) => {
+        $crate::_callback!(@typed $callback_name, $action_ty, ($($var),+), ($($typ),+), $body)
+    };
+    ($callback_name:ident($var:ident : $typ:ty) -> $action_ty:ty $body:block) => {
+        $crate::_callback!(@typed $callback_name, $action_ty, $var, $typ, $body)
+    };
+}
@@ -1,12 +1,186 @@
-use std::{collections::VecDeque, marker::PhantomData};
+use std::{
+    cmp::Reverse,
+    collections::VecDeque,
+    marker::PhantomData,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
-use crate::{AnyAction, Callback, EnablingCondition};
+use crate::{
+    metrics::action_type_name, ActionKindFn, Callback, EnablingCondition, MultipleCallback,
+    RecursionDepth,
+};
+
+/// Order in which actions queued by the reducer are dispatched.
+pub enum QueueOrder<Action> {
+    /// In the order they were queued.
+    Fifo,
+    /// Last queued action first.
+    Lifo,
+    /// Highest key first, actions with equal keys in the order they were
+    /// queued.
+    Priority(fn(&Action) -> u32),
+}
+
+impl<Action> Clone for QueueOrder<Action> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Action> Copy for QueueOrder<Action> {}
+
+impl<Action> std::fmt::Debug for QueueOrder<Action> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fifo => write!(f, "Fifo"),
+            Self::Lifo => write!(f, "Lifo"),
+            Self::Priority(_) => write!(f, "Priority"),
+        }
+    }
+}
+
+/// Handle of an action queued by [`Dispatcher::push`], for cancelling it
+/// with [`Dispatcher::cancel`].
+///
+/// Handles are unique within the store, or the dispatcher if created
+/// outside of a store, so cancelling an action which was already dispatched,
+/// or was queued by another store, has no effect. Clones of the store
+/// generate the same handles as the original.
+///
+/// ```ignore
+/// fn reducer(state: &mut State, action: &ActionWithMeta<Action>, dispatcher: &mut Dispatcher<Action, State>) {
+///     match action.action() {
+///         Action::Request(id) => {
+///             // Dispatched after effects, which send the request.
+///             let timeout = dispatcher.push(Action::Timeout(*id));
+///             state.requests.insert(*id, timeout);
+///         }
+///         Action::Response(id) => {
+///             // Response was dispatched by effects of the request, so
+///             // its timeout is still queued.
+///             if let Some(timeout) = state.requests.remove(id) {
+///                 dispatcher.cancel(timeout);
+///             }
+///         }
+///         ...
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PendingId {
+    source: u64,
+    seq: u64,
+}
+
+/// Generates [`PendingId`]s of a store, or of a dispatcher created outside
+/// of a store.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PendingIds {
+    source: u64,
+    next: u64,
+}
+
+impl PendingIds {
+    pub(crate) fn new() -> Self {
+        static NEXT_SOURCE: AtomicU64 = AtomicU64::new(0);
+        Self {
+            source: NEXT_SOURCE.fetch_add(1, Ordering::Relaxed),
+            next: 0,
+        }
+    }
+
+    fn next(&mut self) -> PendingId {
+        let id = PendingId {
+            source: self.source,
+            seq: self.next,
+        };
+        self.next += 1;
+        id
+    }
+}
+
+/// Actions queued by reducers, which the store hasn't dispatched yet.
+///
+/// Owned by the store and lent to the [`Dispatcher`] of each reducer, so
+/// that they can be cancelled by reducers of later actions.
+pub(crate) struct Queued<Action, State> {
+    /// Queues of reducers of actions being dispatched in
+    /// [`DispatchMode::DepthFirst`](crate::DispatchMode::DepthFirst),
+    /// innermost last.
+    pub(crate) depth_first: Vec<Dispatcher<Action, State>>,
+    /// Actions to dispatch in
+    /// [`DispatchMode::BreadthFirst`](crate::DispatchMode::BreadthFirst),
+    /// with their depth. Only those queued by reducers have an id.
+    pub(crate) breadth_first: VecDeque<(Option<PendingId>, Action, RecursionDepth)>,
+}
+
+impl<Action, State> Queued<Action, State>
+where
+    Action: EnablingCondition<State>,
+{
+    fn cancel(&mut self, id: PendingId) -> Option<Action> {
+        if let Some(action) = self
+            .depth_first
+            .iter_mut()
+            .find_map(|dispatcher| dispatcher.remove(id))
+        {
+            return Some(action);
+        }
+        let i = self
+            .breadth_first
+            .iter()
+            .position(|(queued, ..)| *queued == Some(id))?;
+        self.breadth_first.remove(i).map(|(_, action, _)| action)
+    }
+}
+
+impl<Action, State> Default for Queued<Action, State> {
+    fn default() -> Self {
+        Self {
+            depth_first: Vec::new(),
+            breadth_first: VecDeque::new(),
+        }
+    }
+}
+
+impl<Action: Clone, State> Clone for Queued<Action, State> {
+    fn clone(&self) -> Self {
+        Self {
+            depth_first: self.depth_first.clone(),
+            breadth_first: self.breadth_first.clone(),
+        }
+    }
+}
 
 pub struct Dispatcher<Action, State> {
-    queue: VecDeque<Action>,
+    queue: VecDeque<(PendingId, Action)>,
+    order: QueueOrder<Action>,
+    action_kind: ActionKindFn<Action>,
+    /// Kinds of actions dropped as duplicates, counted by the store in
+    /// [`ActionCounters::dropped_duplicates`](crate::ActionCounters::dropped_duplicates).
+    dropped_duplicates: Vec<&'static str>,
+    /// Actions queued by reducers of earlier actions, lent by the store
+    /// while the reducer runs.
+    pub(crate) earlier: Queued<Action, State>,
+    /// Lent by the store while the reducer runs, like `earlier`.
+    pub(crate) ids: PendingIds,
     _marker: PhantomData<State>,
 }
 
+impl<Action: Clone, State> Clone for Dispatcher<Action, State> {
+    fn clone(&self) -> Self {
+        Self {
+            queue: self.queue.clone(),
+            order: self.order,
+            action_kind: self.action_kind,
+            dropped_duplicates: self.dropped_duplicates.clone(),
+            earlier: self.earlier.clone(),
+            ids: self.ids,
+            _marker: PhantomData,
+        }
+    }
+}
+
 impl<Action, State> Default for Dispatcher<Action, State>
 where
     Action: crate::EnablingCondition<State>,
@@ -21,17 +195,35 @@ where
     Action: crate::EnablingCondition<State>,
 {
     pub fn new() -> Self {
+        Self::with_order(QueueOrder::Fifo)
+    }
+
+    pub fn with_order(order: QueueOrder<Action>) -> Self {
+        Self::for_store(order, action_type_name::<Action>, PendingIds::new())
+    }
+
+    pub(crate) fn for_store(
+        order: QueueOrder<Action>,
+        action_kind: ActionKindFn<Action>,
+        ids: PendingIds,
+    ) -> Self {
         Self {
             queue: VecDeque::new(),
+            order,
+            action_kind,
+            dropped_duplicates: Vec::new(),
+            earlier: Queued::default(),
+            ids,
             _marker: Default::default(),
         }
     }
 
-    pub fn push<T>(&mut self, action: T)
+    /// Queues the action, returning a handle for cancelling it.
+    pub fn push<T>(&mut self, action: T) -> PendingId
     where
         T: Into<Action>,
     {
-        self.queue.push_back(action.into());
+        self.enqueue(action.into())
     }
 
     pub fn push_if_enabled<T>(&mut self, action: T, state: &State, time: crate::Timestamp) -> bool
@@ -39,23 +231,188 @@ where
         T: Into<Action> + EnablingCondition<State>,
     {
         if action.is_enabled(state, time) {
-            self.queue.push_back(action.into());
+            self.enqueue(action.into());
             true
         } else {
             false
         }
     }
 
-    pub fn push_callback<T>(&mut self, callback: Callback<T>, args: T)
+    /// Pushes the action unless an equal action is already queued.
+    ///
+    /// Returns `false` if the action was dropped as a duplicate.
+    pub fn push_dedup<T>(&mut self, action: T) -> bool
+    where
+        T: Into<Action>,
+        Action: PartialEq,
+    {
+        let action = action.into();
+        self.push_unless(action, |queued, action| queued == action)
+    }
+
+    /// Pushes the action unless an action with an equal key is already
+    /// queued.
+    ///
+    /// Returns `false` if the action was dropped as a duplicate.
+    pub fn push_dedup_by_key<T, K, F>(&mut self, action: T, key: F) -> bool
+    where
+        T: Into<Action>,
+        K: PartialEq,
+        F: Fn(&Action) -> K,
+    {
+        let action = action.into();
+        let action_key = key(&action);
+        self.push_unless(action, |queued, _| key(queued) == action_key)
+    }
+
+    /// Number of actions dropped by [`Dispatcher::push_dedup`] and
+    /// [`Dispatcher::push_dedup_by_key`].
+    ///
+    /// The store also counts them in [`ActionMetrics`](crate::ActionMetrics),
+    /// if enabled.
+    pub fn dropped_duplicates(&self) -> usize {
+        self.dropped_duplicates.len()
+    }
+
+    /// Kinds of actions dropped as duplicates, as given by
+    /// [`StoreBuilder::action_kind`](crate::StoreBuilder::action_kind).
+    pub(crate) fn dropped_duplicate_kinds(&self) -> &[&'static str] {
+        &self.dropped_duplicates
+    }
+
+    fn push_unless(
+        &mut self,
+        action: Action,
+        duplicate: impl Fn(&Action, &Action) -> bool,
+    ) -> bool {
+        if self
+            .queue
+            .iter()
+            .any(|(_, queued)| duplicate(queued, &action))
+        {
+            self.dropped_duplicates.push((self.action_kind)(&action));
+            false
+        } else {
+            self.enqueue(action);
+            true
+        }
+    }
+
+    pub fn push_callback<T, A>(&mut self, callback: Callback<T, A>, args: T)
     where
         T: 'static,
-        Action: From<AnyAction>,
+        A: 'static + Into<Action>,
     {
         let action: Action = callback.call(args);
-        self.queue.push_back(action);
+        self.enqueue(action);
+    }
+
+    /// Queues actions returned by the callback, in order.
+    pub fn push_multiple_callback<T, A>(&mut self, callback: MultipleCallback<T, A>, args: T)
+    where
+        T: 'static,
+        A: 'static + Into<Action>,
+    {
+        let actions: Vec<Action> = callback.call(args);
+        for action in actions {
+            self.enqueue(action);
+        }
+    }
+
+    /// Removes a queued action, returning it, or `None` if it was already
+    /// dispatched or cancelled.
+    ///
+    /// Besides actions queued by this reducer, it can cancel those queued by
+    /// reducers of earlier actions, which the store hasn't dispatched yet,
+    /// e.g. because this action was dispatched by their effects.
+    pub fn cancel(&mut self, id: PendingId) -> Option<Action> {
+        self.remove(id).or_else(|| self.earlier.cancel(id))
+    }
+
+    fn remove(&mut self, id: PendingId) -> Option<Action> {
+        let i = self.queue.iter().position(|(queued, _)| *queued == id)?;
+        self.queue.remove(i).map(|(_, action)| action)
+    }
+
+    fn enqueue(&mut self, action: Action) -> PendingId {
+        let id = self.ids.next();
+        self.queue.push_back((id, action));
+        id
+    }
+
+    /// Actions queued by this reducer, in the order they were pushed.
+    pub fn iter(&self) -> impl Iterator<Item = &Action> {
+        self.queue.iter().map(|(_, action)| action)
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Removes and returns queued actions, in the order they would be
+    /// dispatched.
+    pub fn drain(&mut self) -> impl Iterator<Item = Action> + '_ {
+        std::iter::from_fn(move || self.pop())
+    }
+
+    /// Queued actions, in the order they would be dispatched.
+    pub fn into_vec(mut self) -> Vec<Action> {
+        self.drain().collect()
+    }
+
+    /// Removes and returns the next action to dispatch, according to the
+    /// [`QueueOrder`].
+    pub fn pop(&mut self) -> Option<Action> {
+        self.pop_with_id().map(|(_, action)| action)
+    }
+
+    pub(crate) fn pop_with_id(&mut self) -> Option<(PendingId, Action)> {
+        match self.order {
+            QueueOrder::Fifo => self.queue.pop_front(),
+            QueueOrder::Lifo => self.queue.pop_back(),
+            QueueOrder::Priority(key) => {
+                // `min_by_key` returns the first of equal elements.
+                let (i, _) = self
+                    .queue
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, (_, action))| Reverse(key(action)))?;
+                self.queue.remove(i)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Action(u32);
+
+    impl EnablingCondition<()> for Action {}
+
+    #[test]
+    fn cancel() {
+        let mut dispatcher = Dispatcher::<Action, ()>::new();
+        let first = dispatcher.push(Action(1));
+        dispatcher.push(Action(2));
+        assert_eq!(dispatcher.cancel(first), Some(Action(1)));
+        assert_eq!(dispatcher.cancel(first), None);
+        assert_eq!(dispatcher.into_vec(), [Action(2)]);
     }
 
-    pub(crate) fn pop(&mut self) -> Option<Action> {
-        self.queue.pop_front()
+    #[test]
+    fn cancel_foreign_handle() {
+        let mut first = Dispatcher::<Action, ()>::new();
+        let mut second = Dispatcher::<Action, ()>::new();
+        let id = first.push(Action(1));
+        second.push(Action(99));
+        assert_eq!(second.cancel(id), None);
+        assert_eq!(second.len(), 1);
     }
 }
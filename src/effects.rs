@@ -1,4 +1,4 @@
-use crate::{ActionWithMeta, Store};
+use crate::{ActionWithMeta, Store, WallClockIds};
 
-pub type Effects<State, Service, Action> =
-    fn(&mut Store<State, Service, Action>, ActionWithMeta<Action>);
+pub type Effects<State, Service, Action, Ids = WallClockIds> =
+    fn(&mut Store<State, Service, Action, Ids>, ActionWithMeta<Action>);
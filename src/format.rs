@@ -0,0 +1,472 @@
+//! Serialization formats for action logs and snapshots.
+
+use std::{
+    io::{self, BufRead, Write},
+    marker::PhantomData,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{ActionMeta, ActionWithMeta, Recorder};
+
+/// Format of records written to and read from a stream, e.g. an action log
+/// or a snapshot.
+///
+/// ```ignore
+/// let mut file = BufWriter::new(File::create("snapshot.bin")?);
+/// Postcard.write(&mut file, &store.snapshot())?;
+///
+/// let mut file = BufReader::new(File::open("snapshot.bin")?);
+/// // See `Instant` for why the time base of the store is needed.
+/// let snapshot: Snapshot<State> = store
+///     .time_base()
+///     .scope(|| Postcard.read(&mut file))?
+///     .expect("snapshot");
+/// store.restore(snapshot);
+/// ```
+pub trait Format {
+    /// Writes the value as a single record.
+    fn write<T, W>(&self, writer: &mut W, value: &T) -> io::Result<()>
+    where
+        T: Serialize,
+        W: Write;
+
+    /// Reads a record written by [`Format::write`], `None` at the end of
+    /// the stream.
+    ///
+    /// An incomplete last record, e.g. because the process crashed while
+    /// writing it, is treated as the end of the stream.
+    fn read<T, R>(&self, reader: &mut R) -> io::Result<Option<T>>
+    where
+        T: DeserializeOwned,
+        R: BufRead;
+}
+
+/// JSON, one record per line.
+#[cfg(feature = "json")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonLines;
+
+#[cfg(feature = "json")]
+impl Format for JsonLines {
+    fn write<T, W>(&self, writer: &mut W, value: &T) -> io::Result<()>
+    where
+        T: Serialize,
+        W: Write,
+    {
+        serde_json::to_writer(&mut *writer, value)?;
+        writer.write_all(b"\n")
+    }
+
+    fn read<T, R>(&self, reader: &mut R) -> io::Result<Option<T>>
+    where
+        T: DeserializeOwned,
+        R: BufRead,
+    {
+        // Read as bytes, since an incomplete last line may end in the
+        // middle of a UTF-8 character.
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                return Ok(None);
+            }
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            return match serde_json::from_slice(&line) {
+                Ok(value) => Ok(Some(value)),
+                // Last line, which wasn't completely written.
+                Err(_) if !line.ends_with(b"\n") => Ok(None),
+                Err(error) => Err(error.into()),
+            };
+        }
+    }
+}
+
+/// [bincode](https://docs.rs/bincode/1), length-prefixed.
+#[cfg(feature = "bincode")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Bincode;
+
+#[cfg(feature = "bincode")]
+impl Format for Bincode {
+    fn write<T, W>(&self, writer: &mut W, value: &T) -> io::Result<()>
+    where
+        T: Serialize,
+        W: Write,
+    {
+        let bytes = bincode::serialize(value).map_err(io::Error::other)?;
+        write_frame(writer, &bytes)
+    }
+
+    fn read<T, R>(&self, reader: &mut R) -> io::Result<Option<T>>
+    where
+        T: DeserializeOwned,
+        R: BufRead,
+    {
+        read_frame(reader)?
+            .map(|bytes| bincode::deserialize(&bytes).map_err(invalid_data))
+            .transpose()
+    }
+}
+
+/// [postcard](https://docs.rs/postcard), length-prefixed.
+#[cfg(feature = "postcard")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Postcard;
+
+#[cfg(feature = "postcard")]
+impl Format for Postcard {
+    fn write<T, W>(&self, writer: &mut W, value: &T) -> io::Result<()>
+    where
+        T: Serialize,
+        W: Write,
+    {
+        let bytes = postcard::to_allocvec(value).map_err(io::Error::other)?;
+        write_frame(writer, &bytes)
+    }
+
+    fn read<T, R>(&self, reader: &mut R) -> io::Result<Option<T>>
+    where
+        T: DeserializeOwned,
+        R: BufRead,
+    {
+        read_frame(reader)?
+            .map(|bytes| postcard::from_bytes(&bytes).map_err(invalid_data))
+            .transpose()
+    }
+}
+
+/// [MessagePack](https://msgpack.org), length-prefixed.
+#[cfg(feature = "msgpack")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MessagePack;
+
+#[cfg(feature = "msgpack")]
+impl Format for MessagePack {
+    fn write<T, W>(&self, writer: &mut W, value: &T) -> io::Result<()>
+    where
+        T: Serialize,
+        W: Write,
+    {
+        let bytes = rmp_serde::to_vec(value).map_err(io::Error::other)?;
+        write_frame(writer, &bytes)
+    }
+
+    fn read<T, R>(&self, reader: &mut R) -> io::Result<Option<T>>
+    where
+        T: DeserializeOwned,
+        R: BufRead,
+    {
+        read_frame(reader)?
+            .map(|bytes| rmp_serde::from_slice(&bytes).map_err(invalid_data))
+            .transpose()
+    }
+}
+
+/// Writes the bytes prefixed by their length, as little-endian `u32`.
+#[cfg(any(feature = "bincode", feature = "postcard", feature = "msgpack"))]
+fn write_frame<W: Write>(writer: &mut W, bytes: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(bytes.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "record too large"))?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(bytes)
+}
+
+/// Reads bytes written by [`write_frame`], `None` at the end of the stream,
+/// or if the last frame is incomplete.
+#[cfg(any(feature = "bincode", feature = "postcard", feature = "msgpack"))]
+fn read_frame<R: BufRead>(reader: &mut R) -> io::Result<Option<Vec<u8>>> {
+    use std::io::Read;
+
+    let mut len = Vec::with_capacity(4);
+    reader.take(4).read_to_end(&mut len)?;
+    let Ok(len) = <[u8; 4]>::try_from(len) else {
+        return Ok(None);
+    };
+    let len = u32::from_le_bytes(len) as usize;
+    // Not allocated upfront, since the length may be corrupted.
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    Ok((bytes.len() == len).then_some(bytes))
+}
+
+#[cfg(any(feature = "bincode", feature = "postcard", feature = "msgpack"))]
+fn invalid_data<E>(error: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Recorder writing dispatched actions to a stream in the given format,
+/// which can be read by [`ActionLogReader`].
+///
+/// Each action is flushed before it's passed to the reducer, so the log can
+/// be used as a write-ahead log, along with a [`Snapshot`](crate::Snapshot).
+/// Panics if writing fails, since the log would be incomplete.
+///
+/// ```ignore
+/// let log = File::options().append(true).create(true).open("actions.log")?;
+/// let store = Store::builder(reducer, effects, service, State::default())
+///     .recorder(ActionLogWriter::new(Postcard, BufWriter::new(log)))
+///     .build()?;
+/// ```
+pub struct ActionLogWriter<F, W> {
+    format: F,
+    writer: W,
+}
+
+impl<F, W> ActionLogWriter<F, W> {
+    pub fn new(format: F, writer: W) -> Self {
+        Self { format, writer }
+    }
+}
+
+impl<Action, F, W> Recorder<Action> for ActionLogWriter<F, W>
+where
+    Action: Serialize,
+    F: Format,
+    W: Write,
+{
+    fn record(&mut self, action: &ActionWithMeta<Action>) {
+        // Written as a tuple, since `ActionWithMeta` flattens the action,
+        // which formats that aren't self-describing can't deserialize.
+        let record = (action.meta(), action.action());
+        let result = self
+            .format
+            .write(&mut self.writer, &record)
+            .and_then(|_| self.writer.flush());
+        if let Err(error) = result {
+            panic!("failed to write action log: {error}");
+        }
+    }
+}
+
+/// Reads actions written by [`ActionLogWriter`], e.g. to pass them to
+/// [`Store::replay`](crate::Store::replay).
+///
+/// Ends at an incomplete last action, e.g. if the process crashed while
+/// writing it, since it wasn't passed to the reducer either.
+///
+/// ```ignore
+/// let log = BufReader::new(File::open("actions.log")?);
+/// let actions = ActionLogReader::new(Postcard, log).collect::<io::Result<Vec<_>>>()?;
+/// store.replay(actions);
+/// ```
+pub struct ActionLogReader<Action, F, R> {
+    format: F,
+    reader: R,
+    _marker: PhantomData<fn() -> Action>,
+}
+
+impl<Action, F, R> ActionLogReader<Action, F, R> {
+    pub fn new(format: F, reader: R) -> Self {
+        Self {
+            format,
+            reader,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<Action, F, R> Iterator for ActionLogReader<Action, F, R>
+where
+    Action: DeserializeOwned,
+    F: Format,
+    R: BufRead,
+{
+    type Item = io::Result<ActionWithMeta<Action>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self
+            .format
+            .read::<(ActionMeta, Action), _>(&mut self.reader)
+        {
+            Ok(Some((meta, action))) => Some(Ok(meta.with_action(action))),
+            Ok(None) => None,
+            Err(error) => Some(Err(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(any(feature = "bincode", feature = "postcard", feature = "msgpack"))]
+    #[test]
+    fn corrupted_frame_length() {
+        let mut frame = u32::MAX.to_le_bytes().to_vec();
+        frame.extend_from_slice(b"short");
+        assert_eq!(
+            super::read_frame(&mut std::io::Cursor::new(frame)).unwrap(),
+            None
+        );
+    }
+
+    /// Action logs and snapshots written and read by each format.
+    #[cfg(any(
+        feature = "json",
+        feature = "bincode",
+        feature = "postcard",
+        feature = "msgpack"
+    ))]
+    mod round_trip {
+        use std::{io::Cursor, time::Duration};
+
+        use super::super::*;
+        use crate::{
+            test_utils::{restart, RESTART_DELAY},
+            ActionId, Dispatcher, EnablingCondition, Instant, Snapshot, Store, VirtualClock,
+        };
+
+        #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+        enum Action {
+            Add(u32),
+            Rename { name: String },
+        }
+
+        #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct State {
+            sum: u32,
+            name: String,
+            deadline: Instant,
+        }
+
+        impl EnablingCondition<State> for Action {}
+
+        fn reducer(
+            state: &mut State,
+            action: &ActionWithMeta<Action>,
+            _: &mut Dispatcher<Action, State>,
+        ) {
+            match action.action() {
+                Action::Add(n) => state.sum += n,
+                Action::Rename { name } => state.name.clone_from(name),
+            }
+            state.deadline += Duration::from_secs(1);
+        }
+
+        fn effects(_: &mut Store<State, VirtualClock, Action>, _: ActionWithMeta<Action>) {}
+
+        /// The last action has multi-byte characters, so that truncating it
+        /// may split one of them.
+        fn actions() -> Vec<ActionWithMeta<Action>> {
+            let meta = |id| {
+                ActionMeta::new(
+                    ActionId::new_unchecked(id),
+                    ActionId::new_unchecked(id - 1),
+                    1,
+                )
+            };
+            vec![
+                meta(1).with_action(Action::Add(2)),
+                meta(2).with_action(Action::Add(u32::MAX - 2)),
+                meta(3).with_action(Action::Rename {
+                    name: "ünïcode".to_owned(),
+                }),
+            ]
+        }
+
+        fn write_log<F: Format>(format: F, actions: &[ActionWithMeta<Action>]) -> Vec<u8> {
+            let mut log = Vec::new();
+            let mut writer = ActionLogWriter::new(format, &mut log);
+            for action in actions {
+                writer.record(action);
+            }
+            log
+        }
+
+        fn read_log<F: Format>(format: F, log: &[u8]) -> Vec<(ActionId, Action)> {
+            ActionLogReader::new(format, Cursor::new(log))
+                .map(|action| {
+                    let (action, meta) = action.unwrap().split();
+                    (meta.id(), action)
+                })
+                .collect()
+        }
+
+        fn action_log_round_trip<F: Format + Copy>(format: F) {
+            let actions = actions();
+            let expected: Vec<_> = actions
+                .iter()
+                .map(|action| (action.id(), action.action().clone()))
+                .collect();
+            let log = write_log(format, &actions);
+            assert_eq!(read_log(format, &log), expected);
+
+            // Last action wasn't completely written.
+            let complete = write_log(format, &actions[..2]).len();
+            for len in complete..log.len() {
+                let read = read_log(format, &log[..len]);
+                if log[len..] == *b"\n" {
+                    // Only the line terminator of JSON lines is missing.
+                    assert_eq!(read, expected);
+                } else {
+                    assert_eq!(read, expected[..2], "{len}");
+                }
+            }
+        }
+
+        fn snapshot_round_trip<F: Format>(format: F) {
+            let now = Instant::now();
+            let (mut store, mut restarted) = restart(now, || {
+                let state = State {
+                    sum: 0,
+                    name: String::new(),
+                    deadline: now,
+                };
+                Store::builder(reducer, effects, VirtualClock::new(), state)
+            });
+            store.dispatch(Action::Add(3));
+            store.dispatch(Action::Rename {
+                name: "snapshot".to_owned(),
+            });
+
+            let mut bytes = Vec::new();
+            format.write(&mut bytes, &store.snapshot()).unwrap();
+
+            let snapshot: Snapshot<State> = restarted
+                .time_base()
+                .scope(|| format.read(&mut Cursor::new(&bytes)))
+                .unwrap()
+                .unwrap();
+            restarted.restore(snapshot);
+            assert_eq!(restarted.last_action_id(), store.last_action_id());
+            assert_eq!(restarted.state().sum, 3);
+            assert_eq!(restarted.state().name, "snapshot");
+            assert_eq!(
+                restarted.state().deadline,
+                store.state().deadline - RESTART_DELAY
+            );
+        }
+
+        #[cfg(feature = "json")]
+        #[test]
+        fn json_lines() {
+            action_log_round_trip(JsonLines);
+            snapshot_round_trip(JsonLines);
+        }
+
+        #[cfg(feature = "bincode")]
+        #[test]
+        fn bincode() {
+            action_log_round_trip(Bincode);
+            snapshot_round_trip(Bincode);
+        }
+
+        #[cfg(feature = "postcard")]
+        #[test]
+        fn postcard() {
+            action_log_round_trip(Postcard);
+            snapshot_round_trip(Postcard);
+        }
+
+        #[cfg(feature = "msgpack")]
+        #[test]
+        fn msgpack() {
+            action_log_round_trip(MessagePack);
+            snapshot_round_trip(MessagePack);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! Fuzzing of action sequences with fuzzcheck.
+
+use fuzzcheck::DefaultMutator;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{EnablingCondition, Invariant, SequenceRunner, Step, Store, TimeService, VirtualClock};
+
+/// Builder of a fuzz target, which mutates sequences of actions and time
+/// advances and runs them against a fresh store.
+///
+/// Reducer and effects panics and invariant violations are reported as
+/// test failures. Failing inputs are saved by fuzzcheck and can be
+/// minimized with `cargo fuzzcheck <target> --command minify`, then
+/// replayed with [`SequenceRunner::run`].
+///
+/// ```ignore
+/// #[test]
+/// fn fuzz_actions() {
+///     let found_failure = FuzzTarget::new(make_store)
+///         .invariant("balance is never negative", |state| state.balance >= 0)
+///         .launch();
+///     assert!(!found_failure);
+/// }
+/// ```
+pub struct FuzzTarget<State, Service, Action> {
+    runner: SequenceRunner<State, Service, Action>,
+}
+
+impl<State, Service, Action> FuzzTarget<State, Service, Action>
+where
+    State: 'static,
+    Service: TimeService + 'static,
+    Action:
+        EnablingCondition<State> + DefaultMutator + Serialize + DeserializeOwned + Clone + 'static,
+{
+    /// See [`SequenceRunner::new`].
+    pub fn new(make_store: fn(VirtualClock) -> Store<State, Service, Action>) -> Self {
+        Self {
+            runner: SequenceRunner::new(make_store),
+        }
+    }
+
+    /// Adds an invariant, which must hold after every step.
+    pub fn invariant(mut self, name: &'static str, invariant: Invariant<State>) -> Self {
+        self.runner = self.runner.invariant(name, invariant);
+        self
+    }
+
+    /// Test function passed to fuzzcheck, for custom fuzzer configurations.
+    ///
+    /// Panics if an invariant is violated.
+    pub fn test_function(self) -> impl Fn(&Vec<Step<Action>>) {
+        let runner = self.runner;
+        move |steps| {
+            if let Err(failure) = runner.run(steps) {
+                panic!("{failure}");
+            }
+        }
+    }
+
+    /// Launches fuzzing with the default mutator, configured by the
+    /// arguments passed by `cargo fuzzcheck`.
+    ///
+    /// Returns `true` if a test failure was found.
+    pub fn launch(self) -> bool {
+        fuzzcheck::fuzz_test(self.test_function())
+            .default_mutator()
+            .serde_serializer()
+            .default_sensor_and_pool()
+            .arguments_from_cargo_fuzzcheck()
+            .launch()
+            .found_test_failure
+    }
+}
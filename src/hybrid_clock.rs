@@ -0,0 +1,102 @@
+use crate::{ActionId, Timestamp};
+
+const NODE_BITS: u32 = 10;
+const LOGICAL_BITS: u32 = 10;
+const NODE_MASK: u64 = (1 << NODE_BITS) - 1;
+const LOGICAL_MASK: u64 = (1 << LOGICAL_BITS) - 1;
+
+/// Hybrid logical clock, generating [`ActionId`]s which can be ordered
+/// causally across nodes.
+///
+/// Id consists of, from the most significant bits:
+/// 1. physical time in nanoseconds from `UNIX_EPOCH`, with the lowest 20
+///    bits cleared, so its resolution is about 1ms.
+/// 2. 10 bits of a logical counter, incremented for ids generated within
+///    the same physical time. When it overflows, physical time is advanced.
+/// 3. 10 bits of the node id, which makes ids of different nodes unique.
+///
+/// So ids are still approximate timestamps, and each id is greater than
+/// all ids generated or [observed](HybridLogicalClock::observe) by the
+/// clock before it.
+///
+/// ```
+/// use redux::{ActionId, HybridLogicalClock, Timestamp};
+///
+/// let mut a = HybridLogicalClock::new(1);
+/// let mut b = HybridLogicalClock::new(2);
+///
+/// let sent = a.tick(Timestamp::new(5_000_000_000));
+/// // Clock of `b` is behind.
+/// b.observe(sent);
+/// let received = b.tick(Timestamp::new(4_000_000_000));
+///
+/// assert!(received > sent);
+/// assert_eq!(HybridLogicalClock::node_id_of(received), 2);
+/// assert_eq!(HybridLogicalClock::logical_of(received), 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HybridLogicalClock {
+    node_id: u16,
+    /// Physical time and logical counter of the last generated or
+    /// observed id, without the node id.
+    last: u64,
+}
+
+impl HybridLogicalClock {
+    pub const MAX_NODE_ID: u16 = NODE_MASK as u16;
+
+    /// Panics if `node_id` is greater than [`HybridLogicalClock::MAX_NODE_ID`].
+    pub fn new(node_id: u16) -> Self {
+        assert!(
+            node_id <= Self::MAX_NODE_ID,
+            "node id {node_id} exceeds {}",
+            Self::MAX_NODE_ID
+        );
+        Self { node_id, last: 0 }
+    }
+
+    /// Clock whose next id is greater than `last`.
+    pub fn starting_after(node_id: u16, last: ActionId) -> Self {
+        let mut clock = Self::new(node_id);
+        clock.observe(last);
+        clock
+    }
+
+    pub fn node_id(&self) -> u16 {
+        self.node_id
+    }
+
+    /// Last generated id.
+    pub fn last(&self) -> ActionId {
+        ActionId::new_unchecked((self.last << NODE_BITS) | self.node_id as u64)
+    }
+
+    /// Generates the id of a local event, e.g. a dispatched action, at the
+    /// given physical time.
+    pub fn tick(&mut self, physical_time: Timestamp) -> ActionId {
+        let physical = (u64::from(physical_time) >> (NODE_BITS + LOGICAL_BITS)) << LOGICAL_BITS;
+        self.last = (self.last + 1).max(physical);
+        self.last()
+    }
+
+    /// Advances the clock past an id received from another node, so that
+    /// ids generated afterwards are greater than it.
+    pub fn observe(&mut self, remote: ActionId) {
+        self.last = self.last.max(u64::from(remote) >> NODE_BITS);
+    }
+
+    /// Physical time of the id, with a resolution of about 1ms.
+    pub fn physical_time_of(id: ActionId) -> Timestamp {
+        Timestamp::new(u64::from(id) & !(NODE_MASK | (LOGICAL_MASK << NODE_BITS)))
+    }
+
+    /// Logical counter of the id.
+    pub fn logical_of(id: ActionId) -> u16 {
+        ((u64::from(id) >> NODE_BITS) & LOGICAL_MASK) as u16
+    }
+
+    /// Id of the node which generated the id.
+    pub fn node_id_of(id: ActionId) -> u16 {
+        (u64::from(id) & NODE_MASK) as u16
+    }
+}
@@ -1,7 +1,7 @@
 use std::{
-    cell::RefCell,
     cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd},
     ops::{Add, AddAssign, Sub, SubAssign},
+    sync::Mutex,
     time::Duration,
 };
 
@@ -13,6 +13,22 @@ use std::time::Instant as InnerInstant;
 #[cfg(target_arch = "wasm32")]
 use wasm_timer::Instant as InnerInstant;
 
+/// Monotonic time, which a [`TimeBase`](crate::TimeBase) converts to
+/// system time.
+///
+/// # Serialization
+///
+/// Serialized as system time, converted by
+/// [`TimeBase::current`](crate::TimeBase::current). **Outside of
+/// [`TimeBase::scope`](crate::TimeBase::scope), that's the global time
+/// base, i.e. the one of the first created store.** State of other stores,
+/// e.g. with a [`VirtualClock`](crate::VirtualClock) service or in the
+/// simulator, would be serialized with wrong system times, without any
+/// error. Serialize and deserialize state with
+/// [`Store::serialize_state`](crate::Store::serialize_state) and
+/// [`Store::deserialize_state`](crate::Store::deserialize_state), and
+/// restore snapshots with [`Store::restore_from`](crate::Store::restore_from),
+/// which use the time base of the store.
 #[derive(Copy, Clone)]
 pub struct Instant {
     inner: InnerInstant,
@@ -38,31 +54,24 @@ impl Ord for Instant {
     }
 }
 
-thread_local! {
-    static INITIAL_AND_DRIFT: RefCell<Option<(SystemTime, InnerInstant, Duration)>> = const { RefCell::new(None) };
-}
+/// Initial system and monotonic time, and drift between them, shared by all
+/// threads so that instants created on different threads are comparable.
+static INITIAL_AND_DRIFT: Mutex<Option<(SystemTime, InnerInstant, Duration)>> = Mutex::new(None);
 
 impl Instant {
     pub fn now() -> Instant {
-        let inner = INITIAL_AND_DRIFT.with_borrow_mut(|initial_and_drift| {
-            let (initial_sys_time, initial_monotonic, drift) = initial_and_drift
-                .get_or_insert_with(|| (SystemTime::now(), InnerInstant::now(), Duration::ZERO));
-
-            let sys_time_passed = SystemTime::now()
-                .duration_since(*initial_sys_time)
-                .unwrap_or_default();
-            let monotonic_now = InnerInstant::now();
-            let monotonic_passed = monotonic_now.duration_since(*initial_monotonic);
-
-            if sys_time_passed > monotonic_passed + *drift {
-                // handling for system suspension/browser tab suspension.
-                *drift = sys_time_passed - monotonic_passed;
-            }
-
-            monotonic_now + *drift
-        });
+        Self {
+            inner: now_and_drift().0,
+        }
+    }
 
-        Self { inner }
+    /// Total time by which system time outpaced monotonic time since the
+    /// first call to [`Instant::now`] in the process, e.g. because the
+    /// system or the browser tab was suspended.
+    ///
+    /// The drift is added to monotonic time returned by [`Instant::now`].
+    pub fn drift() -> Duration {
+        now_and_drift().1
     }
 
     pub fn duration_since(&self, earlier: Instant) -> Duration {
@@ -84,15 +93,55 @@ impl Instant {
         self.checked_duration_since(earlier).unwrap_or_default()
     }
 
+    /// Adds the duration, `None` if the result can't be represented.
+    ///
+    /// ```
+    /// use redux::Instant;
+    /// use std::time::Duration;
+    ///
+    /// assert_eq!(Instant::now().checked_add(Duration::MAX), None);
+    /// ```
     pub fn checked_add(&self, duration: Duration) -> Option<Instant> {
-        Some(*self + duration)
+        #[cfg(not(target_arch = "wasm32"))]
+        return self.inner.checked_add(duration).map(Instant::from);
+        // Floating point time of the browser doesn't overflow.
+        #[cfg(target_arch = "wasm32")]
+        return Some(*self + duration);
     }
 
+    /// Subtracts the duration, `None` if the result can't be represented.
     pub fn checked_sub(&self, duration: Duration) -> Option<Instant> {
-        Some(*self - duration)
+        #[cfg(not(target_arch = "wasm32"))]
+        return self.inner.checked_sub(duration).map(Instant::from);
+        #[cfg(target_arch = "wasm32")]
+        return Some(*self - duration);
     }
 }
 
+/// Current monotonic time, corrected for drift, and the drift.
+fn now_and_drift() -> (InnerInstant, Duration) {
+    // Time is only read and written under the lock, so a panic can't leave
+    // it inconsistent.
+    let mut initial_and_drift = INITIAL_AND_DRIFT
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let (initial_sys_time, initial_monotonic, drift) = initial_and_drift
+        .get_or_insert_with(|| (SystemTime::now(), InnerInstant::now(), Duration::ZERO));
+
+    let sys_time_passed = SystemTime::now()
+        .duration_since(*initial_sys_time)
+        .unwrap_or_default();
+    let monotonic_now = InnerInstant::now();
+    let monotonic_passed = monotonic_now.duration_since(*initial_monotonic);
+
+    if sys_time_passed > monotonic_passed + *drift {
+        // handling for system suspension/browser tab suspension.
+        *drift = sys_time_passed - monotonic_passed;
+    }
+
+    (monotonic_now + *drift, *drift)
+}
+
 impl From<InnerInstant> for Instant {
     fn from(inner: InnerInstant) -> Self {
         Self { inner }
@@ -139,6 +188,33 @@ impl SubAssign<Duration> for Instant {
     }
 }
 
+/// Serialized as the system time, in nanoseconds since `UNIX_EPOCH`, which
+/// it corresponds to in [`TimeBase::current`](crate::TimeBase::current).
+/// See [Serialization](Instant#serialization).
+#[cfg(feature = "serde")]
+impl serde::Serialize for Instant {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::TimeBase::current()
+            .time_of(*self)
+            .serialize(serializer)
+    }
+}
+
+/// Deserialized from the system time, against
+/// [`TimeBase::current`](crate::TimeBase::current).
+/// See [Serialization](Instant#serialization).
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Instant {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let time = crate::Timestamp::deserialize(deserializer)?;
+        crate::TimeBase::current()
+            .to_monotonic(time)
+            .ok_or_else(|| {
+                serde::de::Error::custom(format!("{time} can't be represented as `Instant`"))
+            })
+    }
+}
+
 impl std::fmt::Debug for Instant {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.inner.fmt(f)
@@ -0,0 +1,2 @@
+/// Function checking that an invariant holds in the given state.
+pub type Invariant<State> = fn(&State) -> bool;
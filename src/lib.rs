@@ -3,31 +3,113 @@
 mod instant;
 
 mod timestamp;
-pub use timestamp::{Instant, SystemTime, Timestamp};
+pub use timestamp::{Instant, ParseTimestampError, SystemTime, Timestamp, TimestampOutOfRange};
+
+mod time_base;
+pub(crate) use time_base::monotonic_to_time;
+pub use time_base::TimeBase;
 
 mod action;
 pub use action::*;
 
+mod hybrid_clock;
+pub use hybrid_clock::HybridLogicalClock;
+
+mod action_id_generator;
+pub use action_id_generator::{ActionIdGenerator, SequenceIds, VirtualClockIds, WallClockIds};
+
 mod reducer;
-pub use reducer::Reducer;
+pub use reducer::{run_reducer, Reducer};
 
 mod effects;
 pub use effects::Effects;
 
+mod middleware;
+pub use middleware::Middleware;
+
+mod recorder;
+pub use recorder::Recorder;
+
 mod service;
 pub use service::{Service, TimeService};
 
+mod metrics;
+pub use metrics::{ActionCounters, ActionKindFn, ActionMetrics, MetricsExporter};
+
+mod virtual_clock;
+pub use virtual_clock::VirtualClock;
+
 mod callback;
+pub use callback::{paste, AnyAction, Callback, MultipleCallback};
 #[cfg(feature = "serializable_callbacks")]
-pub use callback::CALLBACKS;
-pub use callback::{paste, AnyAction, Callback};
+pub use callback::{CALLBACKS, MULTIPLE_CALLBACKS};
 
 mod store;
-pub(crate) use store::monotonic_to_time;
-pub use store::Store;
+pub use store::{DispatchMode, Store};
+
+mod snapshot;
+pub use snapshot::Snapshot;
+
+mod store_builder;
+pub use store_builder::{StoreBuildError, StoreBuilder};
 
 mod sub_store;
 pub use sub_store::SubStore;
 
 mod dispatcher;
-pub use dispatcher::Dispatcher;
+pub use dispatcher::{Dispatcher, PendingId, QueueOrder};
+
+mod invariant;
+pub use invariant::Invariant;
+
+mod action_sequence;
+pub use action_sequence::{SequenceFailure, SequenceRunner, Step};
+
+#[cfg(feature = "simulator")]
+mod simulator;
+#[cfg(feature = "simulator")]
+pub use simulator::{NetworkConfig, NetworkStats, NodeId, SimulatedService, Simulator};
+
+#[cfg(feature = "model_checker")]
+mod model_checker;
+#[cfg(feature = "model_checker")]
+pub use model_checker::{CheckStats, ModelChecker, SearchStrategy, Violation};
+
+#[cfg(feature = "fuzzing")]
+mod fuzz;
+#[cfg(feature = "fuzzing")]
+pub use fuzz::FuzzTarget;
+
+#[cfg(feature = "proptest")]
+mod property_testing;
+#[cfg(feature = "proptest")]
+pub use property_testing::steps;
+
+#[cfg(feature = "testing")]
+mod test_store;
+#[cfg(feature = "testing")]
+pub use test_store::{DispatchRecord, MockService, TestStore};
+
+#[cfg(all(
+    test,
+    any(
+        feature = "json",
+        feature = "bincode",
+        feature = "postcard",
+        feature = "msgpack"
+    )
+))]
+mod test_utils;
+
+#[cfg(feature = "serde")]
+mod format;
+#[cfg(feature = "bincode")]
+pub use format::Bincode;
+#[cfg(feature = "json")]
+pub use format::JsonLines;
+#[cfg(feature = "msgpack")]
+pub use format::MessagePack;
+#[cfg(feature = "postcard")]
+pub use format::Postcard;
+#[cfg(feature = "serde")]
+pub use format::{ActionLogReader, ActionLogWriter, Format};
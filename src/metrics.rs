@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+/// Function returning the kind of an action, used as a label in metrics.
+pub type ActionKindFn<Action> = fn(&Action) -> &'static str;
+
+/// Default [`ActionKindFn`], returning the type name of the action.
+pub(crate) fn action_type_name<Action>(_: &Action) -> &'static str {
+    std::any::type_name::<Action>()
+}
+
+/// Counters of actions of a single kind.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ActionCounters {
+    /// Enabled actions, passed to the reducer.
+    pub dispatched: u64,
+    /// Actions rejected by the enabling condition in
+    /// [`Store::dispatch`](crate::Store::dispatch).
+    pub rejected_at_dispatch: u64,
+    /// Actions queued by the reducer and rejected by the enabling
+    /// condition when dequeued.
+    pub rejected_from_queue: u64,
+    /// Actions not queued by [`Dispatcher::push_dedup`](crate::Dispatcher::push_dedup)
+    /// or [`Dispatcher::push_dedup_by_key`](crate::Dispatcher::push_dedup_by_key),
+    /// because an equal action was already queued.
+    pub dropped_duplicates: u64,
+}
+
+/// Receives metrics from [`ActionMetrics::export`].
+///
+/// Implemented as an adapter to a metrics library, e.g. one setting
+/// counters of a Prometheus registry.
+pub trait MetricsExporter {
+    /// Reports the current value of a monotonically increasing counter.
+    fn counter(&mut self, name: &str, help: &str, labels: &[(&str, &str)], value: u64);
+}
+
+/// Counters of dispatched and rejected actions, per action kind.
+///
+/// Enabled with [`StoreBuilder::metrics`](crate::StoreBuilder::metrics),
+/// kinds are given by [`StoreBuilder::action_kind`](crate::StoreBuilder::action_kind).
+#[derive(Debug, Default, Clone)]
+pub struct ActionMetrics {
+    kinds: BTreeMap<&'static str, ActionCounters>,
+}
+
+impl ActionMetrics {
+    /// Counters for the given action kind.
+    pub fn get(&self, kind: &str) -> ActionCounters {
+        self.kinds.get(kind).copied().unwrap_or_default()
+    }
+
+    /// Counters of all action kinds seen so far.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &ActionCounters)> {
+        self.kinds.iter().map(|(kind, counters)| (*kind, counters))
+    }
+
+    /// Counters summed over all action kinds.
+    pub fn total(&self) -> ActionCounters {
+        self.kinds
+            .values()
+            .fold(ActionCounters::default(), |total, counters| {
+                ActionCounters {
+                    dispatched: total.dispatched + counters.dispatched,
+                    rejected_at_dispatch: total.rejected_at_dispatch
+                        + counters.rejected_at_dispatch,
+                    rejected_from_queue: total.rejected_from_queue + counters.rejected_from_queue,
+                    dropped_duplicates: total.dropped_duplicates + counters.dropped_duplicates,
+                }
+            })
+    }
+
+    pub fn reset(&mut self) {
+        self.kinds.clear();
+    }
+
+    /// Reports all counters, labeled by action kind, as
+    /// `redux_actions_dispatched_total`, `redux_actions_rejected_total`,
+    /// also labeled by `origin` (`dispatch` or `queue`), and
+    /// `redux_actions_dropped_duplicates_total`.
+    pub fn export<E: MetricsExporter>(&self, exporter: &mut E) {
+        for (kind, counters) in self.iter() {
+            exporter.counter(
+                "redux_actions_dispatched_total",
+                "Number of enabled actions passed to the reducer.",
+                &[("kind", kind)],
+                counters.dispatched,
+            );
+            exporter.counter(
+                "redux_actions_rejected_total",
+                "Number of actions rejected by their enabling condition.",
+                &[("kind", kind), ("origin", "dispatch")],
+                counters.rejected_at_dispatch,
+            );
+            exporter.counter(
+                "redux_actions_rejected_total",
+                "Number of actions rejected by their enabling condition.",
+                &[("kind", kind), ("origin", "queue")],
+                counters.rejected_from_queue,
+            );
+            exporter.counter(
+                "redux_actions_dropped_duplicates_total",
+                "Number of actions not queued, because an equal action was already queued.",
+                &[("kind", kind)],
+                counters.dropped_duplicates,
+            );
+        }
+    }
+
+    pub(crate) fn counters_mut(&mut self, kind: &'static str) -> &mut ActionCounters {
+        self.kinds.entry(kind).or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Formats counters in the Prometheus text format.
+    #[derive(Default)]
+    struct Exporter(Vec<String>);
+
+    impl MetricsExporter for Exporter {
+        fn counter(&mut self, name: &str, _: &str, labels: &[(&str, &str)], value: u64) {
+            let labels = labels
+                .iter()
+                .map(|(name, value)| format!("{name}=\"{value}\""))
+                .collect::<Vec<_>>()
+                .join(",");
+            self.0.push(format!("{name}{{{labels}}} {value}"));
+        }
+    }
+
+    #[test]
+    fn export() {
+        let mut metrics = ActionMetrics::default();
+        *metrics.counters_mut("send") = ActionCounters {
+            dispatched: 4,
+            rejected_at_dispatch: 3,
+            rejected_from_queue: 2,
+            dropped_duplicates: 1,
+        };
+
+        let mut exporter = Exporter::default();
+        metrics.export(&mut exporter);
+        assert_eq!(
+            exporter.0,
+            [
+                r#"redux_actions_dispatched_total{kind="send"} 4"#,
+                r#"redux_actions_rejected_total{kind="send",origin="dispatch"} 3"#,
+                r#"redux_actions_rejected_total{kind="send",origin="queue"} 2"#,
+                r#"redux_actions_dropped_duplicates_total{kind="send"} 1"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn total() {
+        let mut metrics = ActionMetrics::default();
+        metrics.counters_mut("open").dispatched = 1;
+        metrics.counters_mut("send").dispatched = 2;
+        metrics.counters_mut("send").rejected_from_queue = 1;
+        assert_eq!(
+            metrics.total(),
+            ActionCounters {
+                dispatched: 3,
+                rejected_from_queue: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            metrics.iter().map(|(kind, _)| kind).collect::<Vec<_>>(),
+            ["open", "send"]
+        );
+    }
+}
@@ -0,0 +1,6 @@
+use crate::ActionWithMeta;
+
+/// Function signature for a middleware.
+///
+/// Middlewares are called for every enabled action, before the reducer.
+pub type Middleware<State, Service, Action> = fn(&State, &mut Service, &ActionWithMeta<Action>);
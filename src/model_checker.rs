@@ -0,0 +1,336 @@
+//! Exhaustive exploration of the states reachable by dispatching actions.
+
+use std::{
+    collections::{
+        hash_map::{DefaultHasher, Entry},
+        HashMap, VecDeque,
+    },
+    hash::{Hash, Hasher},
+};
+
+use crate::{EnablingCondition, Invariant, Store, TimeService};
+
+/// Order in which [`ModelChecker`] explores states.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SearchStrategy {
+    /// Explores states in order of the number of actions needed to reach
+    /// them, so the first violation found has the shortest trace.
+    #[default]
+    BreadthFirst,
+    /// Explores the longest traces first. Uses less memory, reported
+    /// traces are minimized by removing unnecessary actions.
+    ///
+    /// With [`ModelChecker::max_depth`], states reached again by a shorter
+    /// trace are explored again, so that nothing within the bound is missed.
+    DepthFirst,
+}
+
+/// Invariant which was violated, along with the actions leading to it
+/// from the initial state.
+#[derive(Debug, Clone)]
+pub struct Violation<Action> {
+    pub invariant: &'static str,
+    pub trace: Vec<Action>,
+}
+
+/// Summary of a model checking run which found no violations.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CheckStats {
+    /// Number of distinct states reached.
+    pub states: usize,
+    /// Number of enabled actions dispatched.
+    pub transitions: usize,
+    /// `true` if all reachable states were explored, `false` if the
+    /// exploration was cut short by [`ModelChecker::max_depth`] or
+    /// [`ModelChecker::max_states`].
+    pub complete: bool,
+}
+
+/// Explores states reachable from the initial store by dispatching
+/// candidate actions, and checks that invariants hold in all of them.
+///
+/// Candidate actions for a state are given by the user and filtered by
+/// their enabling conditions. States are deduplicated by their hash, so
+/// services should use a [`VirtualClock`](crate::VirtualClock) to keep
+/// times stored in the state deterministic.
+///
+/// ```ignore
+/// let result = ModelChecker::new(store, |state| candidate_actions(state))
+///     .invariant("at most one leader", |state| state.leaders().count() <= 1)
+///     .max_depth(12)
+///     .check();
+///
+/// if let Err(violation) = result {
+///     panic!("{} violated by {:?}", violation.invariant, violation.trace);
+/// }
+/// ```
+pub struct ModelChecker<State, Service, Action> {
+    initial: Store<State, Service, Action>,
+    actions: fn(&State) -> Vec<Action>,
+    invariants: Vec<(&'static str, Invariant<State>)>,
+    strategy: SearchStrategy,
+    max_depth: usize,
+    max_states: usize,
+}
+
+/// Explored state, with the action leading to it from its parent.
+struct TraceNode<Action> {
+    parent: Option<usize>,
+    action: Option<Action>,
+}
+
+impl<State, Service, Action> ModelChecker<State, Service, Action>
+where
+    State: Clone + Hash,
+    Service: TimeService + Clone,
+    Action: EnablingCondition<State> + Clone,
+{
+    pub fn new(initial: Store<State, Service, Action>, actions: fn(&State) -> Vec<Action>) -> Self {
+        Self {
+            initial,
+            actions,
+            invariants: Vec::new(),
+            strategy: SearchStrategy::default(),
+            max_depth: usize::MAX,
+            max_states: usize::MAX,
+        }
+    }
+
+    /// Adds an invariant, which must hold in every reachable state.
+    pub fn invariant(mut self, name: &'static str, invariant: Invariant<State>) -> Self {
+        self.invariants.push((name, invariant));
+        self
+    }
+
+    pub fn strategy(mut self, strategy: SearchStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Maximum number of actions dispatched from the initial state.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Maximum number of distinct states to explore.
+    pub fn max_states(mut self, max_states: usize) -> Self {
+        self.max_states = max_states;
+        self
+    }
+
+    /// Runs the exploration.
+    ///
+    /// Returns the first [`Violation`] found, or statistics of the run.
+    pub fn check(&self) -> Result<CheckStats, Violation<Action>> {
+        let mut stats = CheckStats {
+            complete: true,
+            ..Default::default()
+        };
+        // Fingerprints of reached states, with the smallest depth at which
+        // they were reached.
+        let mut visited = HashMap::new();
+        let mut nodes = vec![TraceNode {
+            parent: None,
+            action: None,
+        }];
+        // Stores to explore, with their index in `nodes` and depth.
+        let mut frontier = VecDeque::new();
+
+        visited.insert(fingerprint(self.initial.state()), 0);
+        stats.states += 1;
+        if let Some(invariant) = self.violated_invariant(self.initial.state()) {
+            return Err(Violation {
+                invariant,
+                trace: Vec::new(),
+            });
+        }
+        frontier.push_back((self.initial.clone(), 0, 0));
+
+        while let Some((store, node, depth)) = match self.strategy {
+            SearchStrategy::BreadthFirst => frontier.pop_front(),
+            SearchStrategy::DepthFirst => frontier.pop_back(),
+        } {
+            if depth >= self.max_depth {
+                stats.complete = false;
+                continue;
+            }
+
+            for action in (self.actions)(store.state()) {
+                if !action.is_enabled(store.state(), store.last_action_id().into()) {
+                    continue;
+                }
+                let mut next = store.clone();
+                next.dispatch(action.clone());
+                stats.transitions += 1;
+
+                let reached_before = match visited.entry(fingerprint(next.state())) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(depth + 1);
+                        false
+                    }
+                    // Depth only matters when it's bounded, otherwise the
+                    // state was already explored or will be.
+                    Entry::Occupied(mut entry)
+                        if self.max_depth != usize::MAX && depth + 1 < *entry.get() =>
+                    {
+                        entry.insert(depth + 1);
+                        true
+                    }
+                    Entry::Occupied(_) => continue,
+                };
+                nodes.push(TraceNode {
+                    parent: Some(node),
+                    action: Some(action),
+                });
+                if reached_before {
+                    frontier.push_back((next, nodes.len() - 1, depth + 1));
+                    continue;
+                }
+                stats.states += 1;
+
+                if let Some(invariant) = self.violated_invariant(next.state()) {
+                    let trace = trace(&nodes, nodes.len() - 1);
+                    let trace = match self.strategy {
+                        SearchStrategy::BreadthFirst => trace,
+                        SearchStrategy::DepthFirst => self.minimize(invariant, trace),
+                    };
+                    return Err(Violation { invariant, trace });
+                }
+
+                if stats.states >= self.max_states {
+                    stats.complete = false;
+                    return Ok(stats);
+                }
+                frontier.push_back((next, nodes.len() - 1, depth + 1));
+            }
+        }
+
+        Ok(stats)
+    }
+
+    fn violated_invariant(&self, state: &State) -> Option<&'static str> {
+        self.invariants
+            .iter()
+            .find(|(_, invariant)| !invariant(state))
+            .map(|(name, _)| *name)
+    }
+
+    /// Removes actions from the trace, one at a time, as long as the
+    /// remaining actions are enabled and still violate the invariant.
+    fn minimize(&self, invariant: &'static str, mut trace: Vec<Action>) -> Vec<Action> {
+        let mut i = 0;
+        while i < trace.len() {
+            let mut candidate = trace.clone();
+            candidate.remove(i);
+            if self.replay_violates(invariant, &candidate) {
+                trace = candidate;
+            } else {
+                i += 1;
+            }
+        }
+        trace
+    }
+
+    fn replay_violates(&self, invariant: &'static str, trace: &[Action]) -> bool {
+        let mut store = self.initial.clone();
+        for action in trace {
+            if !store.dispatch(action.clone()) {
+                return false;
+            }
+        }
+        self.invariants
+            .iter()
+            .any(|(name, check)| *name == invariant && !check(store.state()))
+    }
+}
+
+fn fingerprint<State: Hash>(state: &State) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    state.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Actions leading from the initial state to the given node.
+fn trace<Action: Clone>(nodes: &[TraceNode<Action>], mut node: usize) -> Vec<Action> {
+    let mut trace = Vec::new();
+    while let TraceNode {
+        parent: Some(parent),
+        action: Some(action),
+    } = &nodes[node]
+    {
+        trace.push(action.clone());
+        node = *parent;
+    }
+    trace.reverse();
+    trace
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ActionWithMeta, Dispatcher, SystemTime, Timestamp, VirtualClock};
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Action {
+        Slow,
+        Fast,
+        Bad,
+    }
+
+    impl EnablingCondition<u8> for Action {
+        fn is_enabled(&self, state: &u8, _time: Timestamp) -> bool {
+            match self {
+                Action::Slow | Action::Fast => *state < 10,
+                Action::Bad => *state == 4,
+            }
+        }
+    }
+
+    fn reducer(state: &mut u8, action: &ActionWithMeta<Action>, _: &mut Dispatcher<Action, u8>) {
+        match action.action() {
+            Action::Slow => *state += 1,
+            Action::Fast => *state += 2,
+            Action::Bad => *state = u8::MAX,
+        }
+    }
+
+    fn effects(_: &mut Store<u8, VirtualClock, Action>, _: ActionWithMeta<Action>) {}
+
+    fn checker(strategy: SearchStrategy) -> ModelChecker<u8, VirtualClock, Action> {
+        let store = Store::new(
+            reducer,
+            effects,
+            VirtualClock::new(),
+            SystemTime::UNIX_EPOCH,
+            0,
+        );
+        ModelChecker::new(store, |_| vec![Action::Fast, Action::Slow, Action::Bad])
+            .invariant("not bad", |state| *state != u8::MAX)
+            .strategy(strategy)
+    }
+
+    #[test]
+    fn finds_violation_within_max_depth() {
+        for strategy in [SearchStrategy::BreadthFirst, SearchStrategy::DepthFirst] {
+            // Depth-first search reaches state 4 at depth 3 first, with
+            // `Slow, Fast, Slow`, before reaching it with `Fast, Fast`.
+            let violation = checker(strategy).max_depth(3).check().unwrap_err();
+            assert_eq!(violation.invariant, "not bad");
+            assert_eq!(
+                violation.trace,
+                [Action::Fast, Action::Fast, Action::Bad],
+                "{strategy:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn stops_at_max_depth() {
+        for strategy in [SearchStrategy::BreadthFirst, SearchStrategy::DepthFirst] {
+            let stats = checker(strategy).max_depth(2).check().unwrap();
+            assert!(!stats.complete, "{strategy:?}");
+            assert_eq!(stats.states, 5, "{strategy:?}");
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Property-based testing with proptest.
+
+use std::ops::Range;
+
+use proptest::{
+    collection::vec,
+    prelude::*,
+    test_runner::{Config, TestError, TestRunner},
+};
+
+use crate::{
+    ActionId, ActionMeta, EnablingCondition, SequenceRunner, Step, TimeService, Timestamp,
+};
+
+impl Arbitrary for Timestamp {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        any::<u64>().prop_map(Timestamp::new).boxed()
+    }
+}
+
+impl Arbitrary for ActionId {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        any::<u64>().prop_map(ActionId::new_unchecked).boxed()
+    }
+}
+
+/// Generates metadata where the previous action id isn't greater than
+/// the action id, and the recursion depth is at least 1.
+impl Arbitrary for ActionMeta {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        (any::<u64>(), any::<u64>(), 1..=64u32)
+            .prop_map(|(a, b, depth)| {
+                let (prev, id) = (a.min(b), a.max(b));
+                ActionMeta::new(
+                    ActionId::new_unchecked(id),
+                    ActionId::new_unchecked(prev),
+                    depth,
+                )
+            })
+            .boxed()
+    }
+}
+
+impl<Action> Arbitrary for Step<Action>
+where
+    Action: Arbitrary + 'static,
+{
+    type Parameters = Action::Parameters;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            4 => any_with::<Action>(args).prop_map(Step::Dispatch),
+            1 => (0..10_000u32).prop_map(Step::AdvanceTime),
+        ]
+        .boxed()
+    }
+}
+
+/// Strategy generating sequences of steps, with lengths in `len`.
+pub fn steps<Action>(len: Range<usize>) -> impl Strategy<Value = Vec<Step<Action>>>
+where
+    Action: Arbitrary + 'static,
+{
+    vec(any::<Step<Action>>(), len)
+}
+
+impl<State, Service, Action> SequenceRunner<State, Service, Action>
+where
+    Service: TimeService,
+    Action: EnablingCondition<State> + Clone + std::fmt::Debug,
+{
+    /// Runs sequences generated by `strategy` with proptest, see
+    /// [`SequenceRunner::run`].
+    ///
+    /// Reducer and effects panics and invariant violations fail the test.
+    /// Failing sequences are shrunk, and the minimal one is returned in
+    /// the error.
+    ///
+    /// ```ignore
+    /// SequenceRunner::new(make_store)
+    ///     .invariant("balance is never negative", |state| state.balance >= 0)
+    ///     .proptest(Config::default(), redux::steps(0..100))
+    ///     .unwrap();
+    /// ```
+    pub fn proptest<S>(&self, config: Config, strategy: S) -> Result<(), TestError<S::Value>>
+    where
+        S: Strategy<Value = Vec<Step<Action>>>,
+    {
+        TestRunner::new(config).run(&strategy, |steps| {
+            self.run(&steps)
+                .map(drop)
+                .map_err(|failure| TestCaseError::fail(failure.to_string()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ActionWithMeta, Dispatcher, Store, VirtualClock};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Action {
+        Increment,
+        /// Violates the invariant.
+        Bad,
+    }
+
+    impl Arbitrary for Action {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+            prop_oneof![Just(Action::Increment), Just(Action::Bad)].boxed()
+        }
+    }
+
+    impl EnablingCondition<(u32, bool)> for Action {}
+
+    fn reducer(
+        state: &mut (u32, bool),
+        action: &ActionWithMeta<Action>,
+        _: &mut Dispatcher<Action, (u32, bool)>,
+    ) {
+        match action.action() {
+            Action::Increment => state.0 += 1,
+            Action::Bad => state.1 = true,
+        }
+    }
+
+    fn effects(_: &mut Store<(u32, bool), VirtualClock, Action>, _: ActionWithMeta<Action>) {}
+
+    #[test]
+    fn shrinks_to_minimal_sequence() {
+        let runner = SequenceRunner::new(|clock| {
+            Store::builder(reducer, effects, clock, (0, false))
+                .build()
+                .unwrap()
+        })
+        .invariant("not bad", |(_, bad)| !bad);
+        let config = Config {
+            failure_persistence: None,
+            ..Config::default()
+        };
+
+        match runner.proptest(config, steps(0..50)) {
+            Err(TestError::Fail(reason, steps)) => {
+                assert_eq!(steps, [Step::Dispatch(Action::Bad)]);
+                assert_eq!(
+                    reason.message(),
+                    "invariant `not bad` violated after step 0"
+                );
+            }
+            result => panic!("expected failure, got {result:?}"),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{ActionWithMeta, RecursionDepth};
+
+/// Records actions dispatched by the [`Store`](crate::Store).
+pub trait Recorder<Action> {
+    /// Called for every enabled action, before it's passed to the reducer.
+    fn record(&mut self, action: &ActionWithMeta<Action>);
+
+    /// Called for every action which wasn't dispatched, because it
+    /// wasn't enabled.
+    ///
+    /// `depth` is the recursion depth the action would be dispatched at.
+    #[allow(unused_variables)]
+    fn record_rejected(&mut self, action: &Action, depth: RecursionDepth) {}
+}
+
+impl<Action, F> Recorder<Action> for F
+where
+    F: FnMut(&ActionWithMeta<Action>),
+{
+    fn record(&mut self, action: &ActionWithMeta<Action>) {
+        self(action)
+    }
+}
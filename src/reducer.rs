@@ -1,5 +1,69 @@
-use crate::{ActionWithMeta, Dispatcher};
+use crate::{ActionWithMeta, Dispatcher, EnablingCondition, QueueOrder};
 
 /// Function signature for a reducer.
 pub type Reducer<State, Action> =
     fn(&mut State, &ActionWithMeta<Action>, &mut Dispatcher<Action, State>);
+
+/// Runs the reducer without a store, e.g. in its unit tests.
+///
+/// Returns the new state and the actions queued by the reducer, in the
+/// order a store configured with the same
+/// [`StoreBuilder::queue_order`](crate::StoreBuilder::queue_order) would
+/// dispatch them.
+///
+/// ```ignore
+/// let action = ActionMeta::ZERO.with_action(Action::Connect(peer));
+/// let (state, queued) = run_reducer(reducer, State::default(), &action, QueueOrder::Fifo);
+/// assert_eq!(queued, vec![Action::Dial(peer)]);
+/// ```
+pub fn run_reducer<State, Action>(
+    reducer: Reducer<State, Action>,
+    mut state: State,
+    action: &ActionWithMeta<Action>,
+    order: QueueOrder<Action>,
+) -> (State, Vec<Action>)
+where
+    Action: EnablingCondition<State>,
+{
+    let mut dispatcher = Dispatcher::with_order(order);
+    reducer(&mut state, action, &mut dispatcher);
+    (state, dispatcher.into_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ActionMeta;
+
+    #[derive(Debug, PartialEq)]
+    struct Action(u32);
+
+    impl EnablingCondition<u32> for Action {}
+
+    fn reducer(
+        state: &mut u32,
+        action: &ActionWithMeta<Action>,
+        queue: &mut Dispatcher<Action, u32>,
+    ) {
+        *state += action.action().0;
+        for n in [1, 3, 2] {
+            queue.push(Action(n));
+        }
+    }
+
+    #[test]
+    fn queued_in_dispatch_order() {
+        let action = ActionMeta::ZERO.with_action(Action(5));
+        let run = |order| run_reducer(reducer, 1, &action, order);
+
+        assert_eq!(
+            run(QueueOrder::Fifo),
+            (6, vec![Action(1), Action(3), Action(2)])
+        );
+        assert_eq!(run(QueueOrder::Lifo).1, [Action(2), Action(3), Action(1)]);
+        assert_eq!(
+            run(QueueOrder::Priority(|action| action.0)).1,
+            [Action(3), Action(2), Action(1)]
+        );
+    }
+}
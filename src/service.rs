@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use crate::Instant;
 
 pub trait Service: TimeService {}
@@ -7,4 +9,12 @@ pub trait TimeService {
     fn monotonic_time(&mut self) -> Instant {
         Instant::now()
     }
+
+    /// Drift between system and monotonic time, checked for
+    /// [`StoreBuilder::drift_action`](crate::StoreBuilder::drift_action).
+    ///
+    /// Defaults to [`Instant::drift`].
+    fn drift(&mut self) -> Duration {
+        Instant::drift()
+    }
 }
@@ -0,0 +1,497 @@
+//! Deterministic simulation of multiple [`Store`]s communicating over
+//! a virtual network.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
+
+use crate::{
+    EnablingCondition, Store, StoreBuildError, StoreBuilder, SystemTime, TimeService, VirtualClock,
+};
+
+/// Index of a node in the [`Simulator`].
+pub type NodeId = usize;
+
+/// Service of a simulated node.
+///
+/// Its [`TimeService`] implementation must return the time of the
+/// simulator's [`VirtualClock`], see [`Simulator::clock`].
+pub trait SimulatedService<Action>: TimeService {
+    /// Takes the messages sent by the node since the last call,
+    /// along with their recipients.
+    fn take_outgoing(&mut self) -> Vec<(NodeId, Action)>;
+}
+
+/// Behaviour of the virtual network.
+#[derive(Debug, Clone)]
+pub struct NetworkConfig {
+    /// Minimum latency of a message.
+    pub min_latency: Duration,
+    /// Maximum latency of a message.
+    pub max_latency: Duration,
+    /// Probability of a message being lost, from `0.0` to `1.0`.
+    pub loss: f64,
+    /// Whether messages between the same pair of nodes can be delivered
+    /// in a different order than they were sent.
+    pub reorder: bool,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            min_latency: Duration::from_millis(1),
+            max_latency: Duration::from_millis(50),
+            loss: 0.0,
+            reorder: true,
+        }
+    }
+}
+
+/// Counters of the messages passed through the virtual network.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkStats {
+    pub sent: u64,
+    pub delivered: u64,
+    pub lost: u64,
+    /// Delivered messages whose action was not enabled for the recipient.
+    pub rejected: u64,
+}
+
+struct Message<Action> {
+    to: NodeId,
+    action: Action,
+}
+
+/// Hosts multiple [`Store`]s sharing a [`VirtualClock`] and delivers
+/// messages between them as actions.
+///
+/// Latency, loss and reordering of messages are driven by a random
+/// number generator seeded with the given seed, so the same seed and
+/// the same inputs always produce the same execution.
+pub struct Simulator<State, Service, Action> {
+    nodes: Vec<Store<State, Service, Action>>,
+    clock: VirtualClock,
+    initial_time: SystemTime,
+    config: NetworkConfig,
+    rng: SimRng,
+
+    /// Messages in flight, by time of delivery and sequence number.
+    in_flight: BTreeMap<(Duration, u64), Message<Action>>,
+    next_seq: u64,
+    /// Time of the last scheduled delivery for each link, used to keep
+    /// messages in order when reordering is disabled.
+    last_delivery: HashMap<(NodeId, NodeId), Duration>,
+    stats: NetworkStats,
+}
+
+impl<State, Service, Action> Simulator<State, Service, Action>
+where
+    Service: SimulatedService<Action>,
+    Action: EnablingCondition<State>,
+{
+    pub fn new(seed: u64, config: NetworkConfig) -> Self {
+        Self {
+            nodes: Vec::new(),
+            clock: VirtualClock::new(),
+            initial_time: SystemTime::UNIX_EPOCH,
+            config,
+            rng: SimRng::new(seed),
+
+            in_flight: BTreeMap::new(),
+            next_seq: 0,
+            last_delivery: HashMap::new(),
+            stats: NetworkStats::default(),
+        }
+    }
+
+    /// System time at which the simulation starts.
+    ///
+    /// Defaults to [`SystemTime::UNIX_EPOCH`]. Panics if nodes were
+    /// already added.
+    pub fn with_initial_time(mut self, initial_time: SystemTime) -> Self {
+        assert!(
+            self.nodes.is_empty(),
+            "initial time must be set before adding nodes"
+        );
+        self.initial_time = initial_time;
+        self
+    }
+
+    /// Clock of the simulation, which services of the nodes must use.
+    pub fn clock(&self) -> &VirtualClock {
+        &self.clock
+    }
+
+    /// Time elapsed since the start of the simulation.
+    pub fn elapsed(&self) -> Duration {
+        self.clock.elapsed()
+    }
+
+    pub fn stats(&self) -> NetworkStats {
+        self.stats
+    }
+
+    /// Builds the store and adds it as a node to the simulation.
+    ///
+    /// Initial time of the store is set to the current simulation time.
+    pub fn add_node(
+        &mut self,
+        builder: StoreBuilder<State, Service, Action>,
+    ) -> Result<NodeId, StoreBuildError> {
+        let store = builder
+            .initial_time(self.initial_time + self.clock.elapsed())
+            .initial_monotonic_time(self.clock.now())
+            .build()?;
+        self.nodes.push(store);
+        Ok(self.nodes.len() - 1)
+    }
+
+    pub fn node(&self, id: NodeId) -> &Store<State, Service, Action> {
+        &self.nodes[id]
+    }
+
+    pub fn node_mut(&mut self, id: NodeId) -> &mut Store<State, Service, Action> {
+        &mut self.nodes[id]
+    }
+
+    pub fn nodes(&self) -> &[Store<State, Service, Action>] {
+        &self.nodes
+    }
+
+    /// Number of messages in flight.
+    pub fn pending_messages(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    /// Dispatches an action to the node at the current simulation time.
+    ///
+    /// Returns `true` if the action was enabled.
+    pub fn dispatch<T>(&mut self, node: NodeId, action: T) -> bool
+    where
+        T: Into<Action> + EnablingCondition<State>,
+    {
+        let dispatched = self.nodes[node].dispatch(action);
+        self.send_outgoing(node);
+        dispatched
+    }
+
+    /// Delivers the next message in flight, advancing the clock to its
+    /// delivery time.
+    ///
+    /// Returns `false` if there are no messages in flight.
+    pub fn step(&mut self) -> bool {
+        let Some(((time, _), message)) = self.in_flight.pop_first() else {
+            return false;
+        };
+        self.clock.advance_to(time);
+        self.deliver(message);
+        true
+    }
+
+    /// Delivers all messages due before `elapsed`, then advances the clock to it.
+    pub fn run_until(&mut self, elapsed: Duration) {
+        while self
+            .in_flight
+            .first_key_value()
+            .is_some_and(|((time, _), _)| *time <= elapsed)
+        {
+            self.step();
+        }
+        self.clock.advance_to(elapsed);
+    }
+
+    /// Same as [`Simulator::run_until`], relative to the current time.
+    pub fn run_for(&mut self, duration: Duration) {
+        self.run_until(self.elapsed() + duration);
+    }
+
+    /// Delivers messages until none are in flight, or `max_steps` messages
+    /// were delivered.
+    ///
+    /// Returns `true` if the network became idle.
+    pub fn run_until_idle(&mut self, max_steps: usize) -> bool {
+        for _ in 0..max_steps {
+            if !self.step() {
+                return true;
+            }
+        }
+        self.in_flight.is_empty()
+    }
+
+    fn deliver(&mut self, message: Message<Action>) {
+        let Message { to, action } = message;
+        if self.nodes[to].dispatch(action) {
+            self.stats.delivered += 1;
+        } else {
+            self.stats.rejected += 1;
+        }
+        self.send_outgoing(to);
+    }
+
+    /// Panics if a message is sent to a node which doesn't exist.
+    fn send_outgoing(&mut self, from: NodeId) {
+        let now = self.clock.elapsed();
+        for (to, action) in self.nodes[from].service.take_outgoing() {
+            assert!(
+                to < self.nodes.len(),
+                "node {from} sent a message to unknown node {to}"
+            );
+            self.stats.sent += 1;
+            if self.config.loss > 0.0 && self.rng.next_f64() < self.config.loss {
+                self.stats.lost += 1;
+                continue;
+            }
+
+            let mut time = now + self.latency();
+            if !self.config.reorder {
+                let last = self.last_delivery.entry((from, to)).or_default();
+                time = time.max(*last);
+                *last = time;
+            }
+
+            let seq = self.next_seq;
+            self.next_seq += 1;
+            self.in_flight.insert((time, seq), Message { to, action });
+        }
+    }
+
+    fn latency(&mut self) -> Duration {
+        let NetworkConfig {
+            min_latency,
+            max_latency,
+            ..
+        } = self.config;
+        let range = max_latency.saturating_sub(min_latency).as_nanos() as u64;
+        if range == 0 {
+            return min_latency;
+        }
+        min_latency + Duration::from_nanos(self.rng.next_u64() % (range + 1))
+    }
+}
+
+/// SplitMix64 random number generator.
+///
+/// Used instead of an external crate to keep simulations reproducible
+/// regardless of dependency versions.
+struct SimRng(u64);
+
+impl SimRng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Random number in `0.0..1.0`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ActionWithMeta, Dispatcher, Instant};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Action {
+        /// Sends `Receive` with `n` to the node.
+        Send {
+            to: NodeId,
+            n: u32,
+        },
+        Receive {
+            from: NodeId,
+            n: u32,
+        },
+    }
+
+    impl EnablingCondition<State> for Action {}
+
+    /// Messages received by the node, in the order they were delivered.
+    type State = Vec<(NodeId, u32)>;
+
+    struct Service {
+        id: NodeId,
+        clock: VirtualClock,
+        outgoing: Vec<(NodeId, Action)>,
+    }
+
+    impl TimeService for Service {
+        fn monotonic_time(&mut self) -> Instant {
+            self.clock.now()
+        }
+    }
+
+    impl SimulatedService<Action> for Service {
+        fn take_outgoing(&mut self) -> Vec<(NodeId, Action)> {
+            std::mem::take(&mut self.outgoing)
+        }
+    }
+
+    fn reducer(
+        state: &mut State,
+        action: &ActionWithMeta<Action>,
+        _: &mut Dispatcher<Action, State>,
+    ) {
+        if let Action::Receive { from, n } = *action.action() {
+            state.push((from, n));
+        }
+    }
+
+    fn effects(store: &mut Store<State, Service, Action>, action: ActionWithMeta<Action>) {
+        if let Action::Send { to, n } = *action.action() {
+            let from = store.service.id;
+            store
+                .service
+                .outgoing
+                .push((to, Action::Receive { from, n }));
+        }
+    }
+
+    fn simulator(
+        seed: u64,
+        config: NetworkConfig,
+        nodes: usize,
+    ) -> Simulator<State, Service, Action> {
+        let mut simulator = Simulator::new(seed, config);
+        for id in 0..nodes {
+            let service = Service {
+                id,
+                clock: simulator.clock().clone(),
+                outgoing: Vec::new(),
+            };
+            simulator
+                .add_node(Store::builder(reducer, effects, service, Vec::new()))
+                .unwrap();
+        }
+        simulator
+    }
+
+    /// Nodes 0 and 2 send 20 messages each to node 1.
+    fn send_to_middle(simulator: &mut Simulator<State, Service, Action>) {
+        for n in 0..20 {
+            simulator.dispatch(0, Action::Send { to: 1, n });
+            simulator.dispatch(2, Action::Send { to: 1, n });
+        }
+    }
+
+    fn run(seed: u64) -> (State, NetworkStats, Duration) {
+        let config = NetworkConfig {
+            loss: 0.2,
+            ..Default::default()
+        };
+        let mut simulator = simulator(seed, config, 3);
+        send_to_middle(&mut simulator);
+        assert!(simulator.run_until_idle(usize::MAX));
+        (
+            simulator.node(1).state().clone(),
+            simulator.stats(),
+            simulator.elapsed(),
+        )
+    }
+
+    #[test]
+    fn same_seed_same_execution() {
+        let (received, stats, elapsed) = run(7);
+        assert_eq!(run(7), (received.clone(), stats, elapsed));
+        assert_ne!(run(8).0, received);
+
+        assert_eq!(stats.sent, 40);
+        assert_eq!(stats.delivered + stats.lost, 40);
+        assert!(stats.lost > 0);
+        assert_eq!(received.len() as u64, stats.delivered);
+    }
+
+    #[test]
+    fn fifo_per_link_without_reorder() {
+        let received_from = |reorder| {
+            let config = NetworkConfig {
+                reorder,
+                ..Default::default()
+            };
+            let mut simulator = simulator(1, config, 3);
+            send_to_middle(&mut simulator);
+            simulator.run_until_idle(usize::MAX);
+            let received = simulator.node(1).state().clone();
+            [0, 2].map(|from| {
+                received
+                    .iter()
+                    .filter(|(sender, _)| *sender == from)
+                    .map(|(_, n)| *n)
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        let in_order = (0..20).collect::<Vec<_>>();
+        assert_eq!(received_from(false), [in_order.clone(), in_order.clone()]);
+        assert_ne!(received_from(true)[0], in_order);
+    }
+
+    #[test]
+    fn total_loss() {
+        let config = NetworkConfig {
+            loss: 1.0,
+            ..Default::default()
+        };
+        let mut simulator = simulator(1, config, 3);
+        send_to_middle(&mut simulator);
+        assert_eq!(simulator.pending_messages(), 0);
+        assert!(simulator.run_until_idle(1));
+        assert!(simulator.node(1).state().is_empty());
+        assert_eq!(
+            simulator.stats(),
+            NetworkStats {
+                sent: 40,
+                lost: 40,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn run_until_advances_clock() {
+        let config = NetworkConfig {
+            min_latency: Duration::from_millis(10),
+            max_latency: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let mut simulator = simulator(1, config, 2);
+        let start = simulator.clock().now();
+        simulator.dispatch(0, Action::Send { to: 1, n: 1 });
+
+        simulator.run_until(Duration::from_millis(5));
+        assert_eq!(simulator.elapsed(), Duration::from_millis(5));
+        assert!(simulator.node(1).state().is_empty());
+
+        simulator.run_for(Duration::from_millis(5));
+        assert_eq!(simulator.node(1).state(), &[(0, 1)]);
+
+        simulator.run_until(Duration::from_secs(1));
+        assert_eq!(simulator.elapsed(), Duration::from_secs(1));
+        assert_eq!(
+            simulator.node_mut(1).service().monotonic_time(),
+            start + Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "node 0 sent a message to unknown node 5")]
+    fn send_to_unknown_node() {
+        let mut simulator = simulator(1, NetworkConfig::default(), 2);
+        simulator.dispatch(0, Action::Send { to: 5, n: 1 });
+    }
+
+    #[test]
+    #[should_panic(expected = "initial time must be set before adding nodes")]
+    fn initial_time_after_adding_nodes() {
+        simulator(1, NetworkConfig::default(), 1).with_initial_time(SystemTime::now());
+    }
+}
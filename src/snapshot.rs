@@ -0,0 +1,75 @@
+use crate::{ActionId, TimeBase};
+
+/// State of a [`Store`](crate::Store), along with what's needed to continue
+/// dispatching actions with monotonic ids after a restart.
+///
+/// Taken with [`Store::snapshot`](crate::Store::snapshot) and restored with
+/// [`Store::restore_from`](crate::Store::restore_from). Combined with a log
+/// of actions dispatched after the snapshot, the state can be recovered by
+/// [`Store::replay`](crate::Store::replay):
+///
+/// ```ignore
+/// store.restore_from(&mut serde_json::Deserializer::from_slice(&std::fs::read("snapshot.json")?))?;
+/// store.replay(read_action_log("actions.log")?);
+/// ```
+///
+/// Serialized with the time base of the store the snapshot was taken from,
+/// see [Serialization](crate::Instant#serialization).
+#[derive(Debug, Clone)]
+pub struct Snapshot<State> {
+    pub state: State,
+    /// Id of the last action dispatched before the snapshot.
+    pub last_action_id: ActionId,
+    /// Time base of the store the snapshot was taken from, `None` if
+    /// deserialized.
+    time_base: Option<TimeBase>,
+}
+
+impl<State> Snapshot<State> {
+    pub(crate) fn new(state: State, last_action_id: ActionId, time_base: TimeBase) -> Self {
+        Self {
+            state,
+            last_action_id,
+            time_base: Some(time_base),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SnapshotRepr<State> {
+    state: State,
+    last_action_id: ActionId,
+}
+
+#[cfg(feature = "serde")]
+impl<State: serde::Serialize> serde::Serialize for Snapshot<State> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let repr = SnapshotRepr {
+            state: &self.state,
+            last_action_id: self.last_action_id,
+        };
+        match self.time_base {
+            Some(time_base) => time_base.scope(|| repr.serialize(serializer)),
+            None => repr.serialize(serializer),
+        }
+    }
+}
+
+/// Deserialized against [`TimeBase::current`], use
+/// [`Store::restore_from`](crate::Store::restore_from) to deserialize
+/// against the time base of the store.
+#[cfg(feature = "serde")]
+impl<'de, State: serde::Deserialize<'de>> serde::Deserialize<'de> for Snapshot<State> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let SnapshotRepr {
+            state,
+            last_action_id,
+        } = SnapshotRepr::deserialize(deserializer)?;
+        Ok(Self {
+            state,
+            last_action_id,
+            time_base: None,
+        })
+    }
+}
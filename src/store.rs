@@ -1,10 +1,32 @@
-use std::sync::OnceLock;
+use std::time::Duration;
 
 use crate::{
-    ActionId, ActionMeta, ActionWithMeta, AnyAction, Callback, Dispatcher, Effects,
-    EnablingCondition, Instant, Reducer, SubStore, SystemTime, TimeService, Timestamp,
+    dispatcher::{PendingIds, Queued},
+    ActionId, ActionIdGenerator, ActionKindFn, ActionMeta, ActionMetrics, ActionWithMeta, Callback,
+    Dispatcher, Effects, EnablingCondition, Instant, Middleware, MultipleCallback, QueueOrder,
+    Recorder, RecursionDepth, Reducer, Snapshot, StoreBuilder, SubStore, SystemTime, TimeBase,
+    TimeService, WallClockIds,
 };
 
+/// How actions dispatched by effects and queued by reducers are processed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DispatchMode {
+    /// Actions dispatched by effects are processed immediately, recursively.
+    /// Actions queued by the reducer are processed after the effects.
+    #[default]
+    DepthFirst,
+    /// All actions are appended to a single queue, processed iteratively by
+    /// the top-level [`Store::dispatch`], so the stack doesn't grow with
+    /// chains of actions.
+    ///
+    /// [`Store::dispatch`] called from effects checks the enabling condition
+    /// and queues the action, which is checked again when dequeued.
+    /// [`ActionMeta::depth`] is the number of actions in the chain leading
+    /// to the action, including itself, and that's what
+    /// [`StoreBuilder::max_recursion_depth`] limits in this mode.
+    BreadthFirst,
+}
+
 /// Wraps around State and allows only immutable borrow,
 /// Through `StateWrapper::get` method.
 ///
@@ -38,31 +60,22 @@ impl<T: Clone> Clone for StateWrapper<T> {
     }
 }
 
-/// Monotonic and system time reference points.
-static INITIAL_TIME: OnceLock<(Instant, SystemTime)> = OnceLock::new();
-
-/// Converts monotonic time to nanoseconds since Unix epoch.
-///
-/// If `None` passed, returns result for current time.
-pub fn monotonic_to_time(time: Option<Instant>) -> u64 {
-    let (monotonic, system) = INITIAL_TIME.get_or_init(|| (Instant::now(), SystemTime::now()));
-    let time_passed = time.unwrap_or_else(Instant::now).duration_since(*monotonic);
-    system
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .map(|x| x + time_passed)
-        .map(|x| x.as_nanos())
-        .unwrap_or(0) as u64
-}
-
 /// Main struct for the state machine.
 ///
 /// Exposes a [`Store::dispatch`] method, using
 /// which actions can be dispatched, which triggers a
 /// 1. [`Reducer`] - to update the state.
 /// 2. [`Effects`] - to trigger side-effects of the action.
-pub struct Store<State, Service, Action> {
+///
+/// With the `tracing` feature, every dispatch opens a `dispatch` span with
+/// the action kind (see [`StoreBuilder::action_kind`]), id, depth and
+/// whether it was enabled, containing `reducer` and `effects` spans.
+/// In [`DispatchMode::DepthFirst`], actions dispatched by effects or
+/// queued by the reducer are nested in the span of the action which
+/// caused them.
+pub struct Store<State, Service, Action, Ids = WallClockIds> {
     reducer: Reducer<State, Action>,
-    effects: Effects<State, Service, Action>,
+    effects: Effects<State, Service, Action, Ids>,
 
     /// Current State.
     ///
@@ -71,35 +84,108 @@ pub struct Store<State, Service, Action> {
     pub state: StateWrapper<State>,
     pub service: Service,
 
-    initial_monotonic_time: Instant,
-    initial_time: Timestamp,
+    /// Reference points for converting monotonic time to system time.
+    time_base: TimeBase,
 
     /// Current recursion depth of dispatch.
-    recursion_depth: u32,
+    recursion_depth: RecursionDepth,
+    max_recursion_depth: Option<RecursionDepth>,
 
     last_action_id: ActionId,
+
+    middlewares: Vec<Middleware<State, Service, Action>>,
+    recorders: Vec<Box<dyn Recorder<Action> + Send>>,
+
+    action_kind: ActionKindFn<Action>,
+    metrics: Option<ActionMetrics>,
+
+    queue_order: QueueOrder<Action>,
+    queued_before_effects: bool,
+
+    dispatch_mode: DispatchMode,
+    queued: Queued<Action, State>,
+    pending_ids: PendingIds,
+
+    action_ids: Ids,
+
+    drift_action: Option<fn(Duration) -> Action>,
+    /// Minimum drift increase for which the drift action is dispatched.
+    min_drift: Duration,
+    /// Drift when it was last reported.
+    last_drift: Duration,
 }
 
-impl<State, Service, Action> Store<State, Service, Action>
+impl<State, Service, Action, Ids> Store<State, Service, Action, Ids>
 where
     Service: TimeService,
     Action: EnablingCondition<State>,
+    Ids: ActionIdGenerator,
 {
     /// Creates a new store.
+    ///
+    /// Use [`Store::builder`] for configuring optional parts of the store.
     pub fn new(
         reducer: Reducer<State, Action>,
-        effects: Effects<State, Service, Action>,
-        mut service: Service,
+        effects: Effects<State, Service, Action, Ids>,
+        service: Service,
         initial_time: SystemTime,
         initial_state: State,
-    ) -> Self {
-        let initial_monotonic_time = service.monotonic_time();
-        let initial_time_nanos = initial_time
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .map(|x| x.as_nanos())
-            .unwrap_or(0);
+    ) -> Self
+    where
+        Ids: Default,
+    {
+        Self::from_builder(
+            StoreBuilder::new(reducer, effects, service, initial_state).initial_time(initial_time),
+        )
+    }
 
-        INITIAL_TIME.get_or_init(move || (initial_monotonic_time, initial_time));
+    /// Creates a new [`StoreBuilder`].
+    pub fn builder(
+        reducer: Reducer<State, Action>,
+        effects: Effects<State, Service, Action, Ids>,
+        service: Service,
+        initial_state: State,
+    ) -> StoreBuilder<State, Service, Action, Ids>
+    where
+        Ids: Default,
+    {
+        StoreBuilder::new(reducer, effects, service, initial_state)
+    }
+
+    pub(crate) fn from_builder(builder: StoreBuilder<State, Service, Action, Ids>) -> Self {
+        let StoreBuilder {
+            reducer,
+            effects,
+            mut service,
+            initial_state,
+            initial_time,
+            initial_monotonic_time,
+            initial_action_id,
+            max_recursion_depth,
+            middlewares,
+            recorders,
+            action_kind,
+            metrics,
+            queue_order,
+            queued_before_effects,
+            dispatch_mode,
+            mut action_ids,
+            drift_action,
+            min_drift,
+        } = builder;
+
+        let initial_time = initial_time.unwrap_or_else(SystemTime::now);
+        let initial_monotonic_time =
+            initial_monotonic_time.unwrap_or_else(|| service.monotonic_time());
+        let time_base = TimeBase::new(initial_monotonic_time, initial_time);
+        time_base.init_global();
+
+        let last_action_id =
+            initial_action_id.unwrap_or_else(|| action_ids.initial_id(time_base.time()));
+        let last_drift = match drift_action {
+            Some(_) => service.drift(),
+            None => Duration::ZERO,
+        };
 
         Self {
             reducer,
@@ -109,11 +195,30 @@ where
                 inner: initial_state,
             },
 
-            initial_monotonic_time,
-            initial_time: Timestamp::new(initial_time_nanos as u64),
+            time_base,
 
             recursion_depth: 0,
-            last_action_id: ActionId::new_unchecked(initial_time_nanos as u64),
+            max_recursion_depth,
+            last_action_id,
+
+            middlewares,
+            recorders,
+
+            action_kind,
+            metrics: metrics.then(ActionMetrics::default),
+
+            queue_order,
+            queued_before_effects,
+
+            dispatch_mode,
+            queued: Queued::default(),
+            pending_ids: PendingIds::new(),
+
+            action_ids,
+
+            drift_action,
+            min_drift,
+            last_drift,
         }
     }
 
@@ -128,9 +233,144 @@ where
         &mut self.service
     }
 
+    /// Id of the last dispatched action.
+    #[inline(always)]
+    pub fn last_action_id(&self) -> ActionId {
+        self.last_action_id
+    }
+
+    /// Reference points for converting monotonic time to system time.
+    #[inline(always)]
+    pub fn time_base(&self) -> &TimeBase {
+        &self.time_base
+    }
+
+    /// Kind of the action, as given by [`StoreBuilder::action_kind`].
+    pub fn action_kind(&self, action: &Action) -> &'static str {
+        (self.action_kind)(action)
+    }
+
+    /// Counters of dispatched and rejected actions, `None` unless enabled
+    /// with [`StoreBuilder::metrics`].
+    pub fn metrics(&self) -> Option<&ActionMetrics> {
+        self.metrics.as_ref()
+    }
+
+    /// Resets all counters of [`Store::metrics`].
+    pub fn reset_metrics(&mut self) {
+        if let Some(metrics) = &mut self.metrics {
+            metrics.reset();
+        }
+    }
+
+    /// Serializes the state with the time base of this store, unlike
+    /// serializing [`Store::state`] directly, see
+    /// [Serialization](Instant#serialization).
+    ///
+    /// ```ignore
+    /// let mut json = Vec::new();
+    /// store.serialize_state(&mut serde_json::Serializer::new(&mut json))?;
+    /// // In another store, e.g. after a restart.
+    /// let state: State = store.deserialize_state(&mut serde_json::Deserializer::from_slice(&json))?;
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn serialize_state<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        State: serde::Serialize,
+        S: serde::Serializer,
+    {
+        self.time_base
+            .scope(|| serde::Serialize::serialize(self.state(), serializer))
+    }
+
+    /// Deserializes a state serialized by [`Store::serialize_state`], with
+    /// the time base of this store.
+    #[cfg(feature = "serde")]
+    pub fn deserialize_state<'de, D>(&self, deserializer: D) -> Result<State, D::Error>
+    where
+        State: serde::Deserialize<'de>,
+        D: serde::Deserializer<'de>,
+    {
+        self.time_base.scope(|| State::deserialize(deserializer))
+    }
+
+    /// Snapshot of the state, which can be serialized and later restored
+    /// with [`Store::restore_from`].
+    pub fn snapshot(&self) -> Snapshot<&State> {
+        Snapshot::new(self.state(), self.last_action_id, self.time_base)
+    }
+
+    /// Replaces the state with the snapshot's. Ids of actions dispatched
+    /// afterwards are greater than [`Snapshot::last_action_id`].
+    ///
+    /// A serialized snapshot must be deserialized within
+    /// `store.time_base().scope(..)`, see [Serialization](Instant#serialization),
+    /// or with [`Store::restore_from`].
+    ///
+    /// Actions queued in [`DispatchMode::BreadthFirst`] are discarded.
+    /// Panics if called during dispatch.
+    pub fn restore(&mut self, snapshot: Snapshot<State>) {
+        assert_eq!(
+            self.recursion_depth, 0,
+            "snapshot can't be restored during dispatch"
+        );
+        self.state = StateWrapper {
+            inner: snapshot.state,
+        };
+        self.last_action_id = snapshot.last_action_id;
+        self.queued = Queued::default();
+    }
+
+    /// Deserializes a snapshot serialized from [`Store::snapshot`], with
+    /// the time base of this store, and restores it with [`Store::restore`].
+    ///
+    /// ```ignore
+    /// let json = std::fs::read("snapshot.json")?;
+    /// store.restore_from(&mut serde_json::Deserializer::from_slice(&json))?;
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn restore_from<'de, D>(&mut self, deserializer: D) -> Result<(), D::Error>
+    where
+        State: serde::Deserialize<'de>,
+        D: serde::Deserializer<'de>,
+    {
+        let snapshot = self
+            .time_base
+            .scope(|| serde::Deserialize::deserialize(deserializer))?;
+        self.restore(snapshot);
+        Ok(())
+    }
+
+    /// Runs the reducer for previously dispatched actions, e.g. from an
+    /// action log, without running effects, middlewares or recorders.
+    ///
+    /// Actions with ids up to [`Store::last_action_id`] are skipped, since
+    /// they're already reflected in the state, e.g. of the restored
+    /// snapshot. Actions queued by the reducer are discarded, since they are
+    /// logged too, if they were enabled.
+    ///
+    /// Returns the number of replayed actions.
+    pub fn replay<I>(&mut self, actions: I) -> usize
+    where
+        I: IntoIterator<Item = ActionWithMeta<Action>>,
+    {
+        let mut replayed = 0;
+        for action in actions {
+            if action.id() <= self.last_action_id {
+                continue;
+            }
+            let mut dispatcher =
+                Dispatcher::for_store(self.queue_order, self.action_kind, self.pending_ids);
+            self.dispatch_reducer(&action, &mut dispatcher);
+            self.last_action_id = action.id();
+            replayed += 1;
+        }
+        replayed
+    }
+
     /// Convert monotonic time to system clock in nanoseconds from epoch.
     pub fn monotonic_to_time(&self, monotonic_time: Instant) -> u64 {
-        monotonic_to_time(Some(monotonic_time))
+        self.time_base.to_time(monotonic_time).into()
     }
 
     /// Dispatch an Action.
@@ -143,7 +383,11 @@ where
     where
         T: Into<Action> + EnablingCondition<State>,
     {
+        self.check_drift();
         if !action.is_enabled(self.state(), self.last_action_id.into()) {
+            if self.observes_rejected() {
+                self.record_rejected(&action.into(), false);
+            }
             return false;
         }
         self.dispatch_enabled(action.into());
@@ -151,15 +395,37 @@ where
         true
     }
 
-    pub fn dispatch_callback<T>(&mut self, callback: Callback<T>, args: T) -> bool
+    /// Dispatch the action returned by the callback.
+    pub fn dispatch_callback<T, A>(&mut self, callback: Callback<T, A>, args: T) -> bool
     where
         T: 'static,
-        Action: From<AnyAction> + EnablingCondition<State>,
+        A: 'static + Into<Action>,
     {
         let action: Action = callback.call(args);
         self.dispatch(action)
     }
 
+    /// Dispatch actions returned by the callback, in order.
+    ///
+    /// Each action is checked against its enabling condition when it's
+    /// dispatched. Returns `true` if at least one of them was enabled.
+    pub fn dispatch_multiple_callback<T, A>(
+        &mut self,
+        callback: MultipleCallback<T, A>,
+        args: T,
+    ) -> bool
+    where
+        T: 'static,
+        A: 'static + Into<Action>,
+    {
+        let actions: Vec<Action> = callback.call(args);
+        let mut dispatched = false;
+        for action in actions {
+            dispatched |= self.dispatch(action);
+        }
+        dispatched
+    }
+
     /// Dispatch an Action (For `SubStore`).
     ///
     /// Returns `true` if the action was enabled, hence if it was dispatched
@@ -172,10 +438,14 @@ where
         <Self as SubStore<State, S>>::SubAction: Into<Action>,
         Self: SubStore<State, S>,
     {
+        self.check_drift();
         if !action.is_enabled(
             <Self as SubStore<State, S>>::state(self),
             self.last_action_id.into(),
         ) {
+            if self.observes_rejected() {
+                self.record_rejected(&action.into().into(), false);
+            }
             return false;
         }
         self.dispatch_enabled(action.into().into());
@@ -183,34 +453,159 @@ where
         true
     }
 
+    /// Advances the clock of the store past an action id received from
+    /// another node, so that actions dispatched afterwards have greater ids.
+    ///
+    /// Has no effect unless supported by the [`ActionIdGenerator`], e.g.
+    /// [`HybridLogicalClock`](crate::HybridLogicalClock).
+    pub fn observe_remote(&mut self, remote: ActionId) {
+        self.action_ids.observe_remote(remote);
+    }
+
     fn update_action_id(&mut self) -> ActionId {
         let prev_action_id = self.last_action_id;
-        let now = self.initial_time
-            + self
-                .service
-                .monotonic_time()
-                .duration_since(self.initial_monotonic_time);
-
-        let t = (Timestamp::from(prev_action_id) + 1).max(now);
-        self.last_action_id = ActionId::new_unchecked(t.into());
+        let now = self.time_base.to_time(self.service.monotonic_time());
+
+        self.last_action_id = self.action_ids.next_id(prev_action_id, now);
         prev_action_id
     }
 
     /// Dispatches action without checking the enabling condition.
     fn dispatch_enabled(&mut self, action: Action) {
+        match self.dispatch_mode {
+            DispatchMode::DepthFirst => self.run_action(action),
+            DispatchMode::BreadthFirst => {
+                self.queued
+                    .breadth_first
+                    .push_back((None, action, self.recursion_depth + 1));
+                // Only the top-level dispatch drains the queue.
+                if self.recursion_depth == 0 {
+                    self.dispatch_pending();
+                }
+            }
+        }
+    }
+
+    /// Dispatches queued actions in [`DispatchMode::BreadthFirst`].
+    fn dispatch_pending(&mut self) {
+        while let Some((_, action, depth)) = self.queued.breadth_first.pop_front() {
+            self.recursion_depth = depth - 1;
+            if action.is_enabled(self.state(), self.last_action_id.into()) {
+                self.run_action(action);
+            } else {
+                self.record_rejected(&action, true);
+            }
+        }
+        self.recursion_depth = 0;
+    }
+
+    /// Runs middlewares, reducer and effects of the action.
+    fn run_action(&mut self, action: Action) {
+        if let Some(max) = self.max_recursion_depth {
+            if self.recursion_depth >= max {
+                panic!("max recursion depth of dispatch ({max}) exceeded");
+            }
+        }
+
         let prev = self.update_action_id();
         self.recursion_depth += 1;
 
+        if let Some(metrics) = &mut self.metrics {
+            metrics.counters_mut((self.action_kind)(&action)).dispatched += 1;
+        }
+
         let action_with_meta =
             ActionMeta::new(self.last_action_id, prev, self.recursion_depth).with_action(action);
 
-        let mut dispatcher = Dispatcher::new();
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "dispatch",
+            kind = (self.action_kind)(action_with_meta.action()),
+            id = u64::from(self.last_action_id),
+            depth = self.recursion_depth,
+            enabled = true,
+        )
+        .entered();
+
+        for recorder in &mut self.recorders {
+            recorder.record(&action_with_meta);
+        }
+        for middleware in &self.middlewares {
+            middleware(self.state.get(), &mut self.service, &action_with_meta);
+        }
+
+        let mut dispatcher =
+            Dispatcher::for_store(self.queue_order, self.action_kind, self.pending_ids);
         self.dispatch_reducer(&action_with_meta, &mut dispatcher);
-        self.dispatch_effects(action_with_meta, dispatcher);
+        if let Some(metrics) = &mut self.metrics {
+            for kind in dispatcher.dropped_duplicate_kinds() {
+                metrics.counters_mut(kind).dropped_duplicates += 1;
+            }
+        }
+        match self.dispatch_mode {
+            DispatchMode::DepthFirst => self.dispatch_effects(action_with_meta, dispatcher),
+            DispatchMode::BreadthFirst => self.queue_effects(action_with_meta, dispatcher),
+        }
 
         self.recursion_depth -= 1;
     }
 
+    /// Dispatches the [`StoreBuilder::drift_action`] if the drift increased
+    /// enough since it was last reported. Only checked by top-level
+    /// dispatches.
+    fn check_drift(&mut self) {
+        let Some(drift_action) = self.drift_action else {
+            return;
+        };
+        if self.recursion_depth > 0 {
+            return;
+        }
+        let drift = self.service.drift();
+        let increase = drift.saturating_sub(self.last_drift);
+        if increase.is_zero() || increase < self.min_drift {
+            return;
+        }
+        let action = drift_action(increase);
+        self.last_drift = drift;
+
+        if action.is_enabled(self.state(), self.last_action_id.into()) {
+            self.dispatch_enabled(action);
+        } else {
+            self.record_rejected(&action, false);
+        }
+    }
+
+    /// Whether rejected actions are recorded or counted, so that they must
+    /// be converted to `Action`.
+    fn observes_rejected(&self) -> bool {
+        cfg!(feature = "tracing") || !self.recorders.is_empty() || self.metrics.is_some()
+    }
+
+    /// Records and counts an action rejected by its enabling condition,
+    /// either in [`Store::dispatch`] or when dequeued.
+    fn record_rejected(&mut self, action: &Action, from_queue: bool) {
+        #[cfg(feature = "tracing")]
+        tracing::debug_span!(
+            "dispatch",
+            kind = (self.action_kind)(action),
+            depth = self.recursion_depth + 1,
+            enabled = false,
+        )
+        .in_scope(|| {});
+
+        if let Some(metrics) = &mut self.metrics {
+            let counters = metrics.counters_mut((self.action_kind)(action));
+            if from_queue {
+                counters.rejected_from_queue += 1;
+            } else {
+                counters.rejected_at_dispatch += 1;
+            }
+        }
+        for recorder in &mut self.recorders {
+            recorder.record_rejected(action, self.recursion_depth + 1);
+        }
+    }
+
     /// Runs the reducer.
     #[inline(always)]
     fn dispatch_reducer(
@@ -218,33 +613,89 @@ where
         action_with_id: &ActionWithMeta<Action>,
         dispatcher: &mut Dispatcher<Action, State>,
     ) {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!("reducer").entered();
+
+        // Lend actions queued by earlier reducers, so they can be cancelled.
+        dispatcher.earlier = std::mem::take(&mut self.queued);
         (self.reducer)(self.state.get_mut(), action_with_id, dispatcher);
+        self.queued = std::mem::take(&mut dispatcher.earlier);
+        self.pending_ids = dispatcher.ids;
     }
 
-    /// Runs the effects.
+    /// Runs the effects and dispatches actions queued by the reducer,
+    /// in the order configured by [`StoreBuilder::queued_before_effects`].
     #[inline(always)]
     fn dispatch_effects(
+        &mut self,
+        action_with_id: ActionWithMeta<Action>,
+        queued: Dispatcher<Action, State>,
+    ) {
+        // Kept in the store until dispatched, so that reducers of actions
+        // dispatched in the meantime can cancel them.
+        self.queued.depth_first.push(queued);
+        if self.queued_before_effects {
+            self.dispatch_queued();
+            self.run_effects(action_with_id);
+        } else {
+            self.run_effects(action_with_id);
+            self.dispatch_queued();
+        }
+        self.queued.depth_first.pop();
+    }
+
+    /// Runs the effects and appends actions queued by the reducer to the
+    /// queue of [`DispatchMode::BreadthFirst`].
+    fn queue_effects(
         &mut self,
         action_with_id: ActionWithMeta<Action>,
         mut queued: Dispatcher<Action, State>,
     ) {
-        // First the effects for this specific action must be handled
+        let depth = self.recursion_depth + 1;
+        if self.queued_before_effects {
+            while let Some((id, action)) = queued.pop_with_id() {
+                self.queued
+                    .breadth_first
+                    .push_back((Some(id), action, depth));
+            }
+            self.run_effects(action_with_id);
+        } else {
+            self.run_effects(action_with_id);
+            while let Some((id, action)) = queued.pop_with_id() {
+                self.queued
+                    .breadth_first
+                    .push_back((Some(id), action, depth));
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn run_effects(&mut self, action_with_id: ActionWithMeta<Action>) {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!("effects").entered();
+
         (self.effects)(self, action_with_id);
+    }
 
-        // Then dispatch all actions enqueued by the reducer
-        while let Some(action) = queued.pop() {
+    /// Dispatches actions enqueued by the reducer, which are on top of
+    /// [`Queued::depth_first`].
+    fn dispatch_queued(&mut self) {
+        while let Some(action) = self.queued.depth_first.last_mut().and_then(Dispatcher::pop) {
             if action.is_enabled(self.state(), self.last_action_id.into()) {
                 self.dispatch_enabled(action);
+            } else {
+                self.record_rejected(&action, true);
             }
         }
     }
 }
 
-impl<State, Service, Action> Clone for Store<State, Service, Action>
+impl<State, Service, Action, Ids> Clone for Store<State, Service, Action, Ids>
 where
     State: Clone,
     Service: Clone,
     Action: Clone + EnablingCondition<State>,
+    Ids: Clone,
 {
     fn clone(&self) -> Self {
         Self {
@@ -253,11 +704,805 @@ where
             service: self.service.clone(),
             state: self.state.clone(),
 
-            initial_monotonic_time: self.initial_monotonic_time,
-            initial_time: self.initial_time,
+            time_base: self.time_base,
 
             recursion_depth: self.recursion_depth,
+            max_recursion_depth: self.max_recursion_depth,
             last_action_id: self.last_action_id,
+
+            middlewares: self.middlewares.clone(),
+            // Recorders are tied to the original store.
+            recorders: Vec::new(),
+
+            action_kind: self.action_kind,
+            metrics: self.metrics.clone(),
+
+            queue_order: self.queue_order,
+            queued_before_effects: self.queued_before_effects,
+
+            dispatch_mode: self.dispatch_mode,
+            queued: self.queued.clone(),
+            pending_ids: self.pending_ids,
+
+            action_ids: self.action_ids.clone(),
+
+            drift_action: self.drift_action,
+            min_drift: self.min_drift,
+            last_drift: self.last_drift,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ActionCounters, PendingId, VirtualClock};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Action {
+        Start,
+        Queued(u32),
+        Effect(u32),
+    }
+
+    impl EnablingCondition<Vec<(Action, u32)>> for Action {}
+
+    impl EnablingCondition<Instant> for Action {}
+
+    type State = Vec<(Action, u32)>;
+
+    /// Records actions with their depth. `Start` queues `Queued(1)`,
+    /// `Queued(3)` and `Queued(2)`, and drops a duplicate of `Queued(1)`.
+    fn reducer(
+        state: &mut State,
+        action: &ActionWithMeta<Action>,
+        queue: &mut Dispatcher<Action, State>,
+    ) {
+        state.push((*action.action(), action.depth()));
+        if let Action::Start = action.action() {
+            for n in [1, 3, 2] {
+                queue.push(Action::Queued(n));
+            }
+            queue.push_dedup(Action::Queued(1));
+        }
+    }
+
+    /// `Start` dispatches `Effect(1)` and `Effect(2)`, `Effect(1)`
+    /// dispatches `Effect(10)`.
+    fn effects(store: &mut Store<State, VirtualClock, Action>, action: ActionWithMeta<Action>) {
+        match action.action() {
+            Action::Start => {
+                store.dispatch(Action::Effect(1));
+                store.dispatch(Action::Effect(2));
+            }
+            Action::Effect(1) => {
+                store.dispatch(Action::Effect(10));
+            }
+            _ => {}
+        }
+    }
+
+    fn dispatched(builder: StoreBuilder<State, VirtualClock, Action>) -> State {
+        let mut store = builder.build().unwrap();
+        store.dispatch(Action::Start);
+        store.state().clone()
+    }
+
+    fn builder() -> StoreBuilder<State, VirtualClock, Action> {
+        Store::builder(reducer, effects, VirtualClock::new(), Vec::new())
+    }
+
+    #[test]
+    fn depth_first() {
+        use Action::*;
+        assert_eq!(
+            dispatched(builder()),
+            [
+                (Start, 1),
+                (Effect(1), 2),
+                (Effect(10), 3),
+                (Effect(2), 2),
+                (Queued(1), 2),
+                (Queued(3), 2),
+                (Queued(2), 2),
+            ]
+        );
+        assert_eq!(
+            dispatched(builder().queued_before_effects()),
+            [
+                (Start, 1),
+                (Queued(1), 2),
+                (Queued(3), 2),
+                (Queued(2), 2),
+                (Effect(1), 2),
+                (Effect(10), 3),
+                (Effect(2), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn breadth_first() {
+        use Action::*;
+        let builder = || builder().dispatch_mode(DispatchMode::BreadthFirst);
+        assert_eq!(
+            dispatched(builder()),
+            [
+                (Start, 1),
+                (Effect(1), 2),
+                (Effect(2), 2),
+                (Queued(1), 2),
+                (Queued(3), 2),
+                (Queued(2), 2),
+                (Effect(10), 3),
+            ]
+        );
+        assert_eq!(
+            dispatched(builder().queued_before_effects()),
+            [
+                (Start, 1),
+                (Queued(1), 2),
+                (Queued(3), 2),
+                (Queued(2), 2),
+                (Effect(1), 2),
+                (Effect(2), 2),
+                (Effect(10), 3),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "max recursion depth of dispatch (2) exceeded")]
+    fn breadth_first_limits_chain_length() {
+        let builder = builder()
+            .dispatch_mode(DispatchMode::BreadthFirst)
+            .max_recursion_depth(2);
+        dispatched(builder);
+    }
+
+    #[test]
+    fn queue_order() {
+        fn queued(builder: StoreBuilder<State, VirtualClock, Action>) -> Vec<u32> {
+            dispatched(builder)
+                .into_iter()
+                .filter_map(|(action, _)| match action {
+                    Action::Queued(n) => Some(n),
+                    _ => None,
+                })
+                .collect()
+        }
+        fn key(action: &Action) -> u32 {
+            match action {
+                Action::Queued(n) => *n,
+                _ => 0,
+            }
+        }
+
+        assert_eq!(queued(builder().queue_order(QueueOrder::Fifo)), [1, 3, 2]);
+        assert_eq!(queued(builder().queue_order(QueueOrder::Lifo)), [2, 3, 1]);
+        assert_eq!(
+            queued(builder().queue_order(QueueOrder::Priority(key))),
+            [3, 2, 1]
+        );
+    }
+
+    #[test]
+    fn cancel_action_queued_by_earlier_reducer() {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        enum Action {
+            Request,
+            Response,
+            Timeout,
+        }
+
+        impl EnablingCondition<State> for Action {}
+
+        #[derive(Debug, Clone, Default)]
+        struct State {
+            log: Vec<Action>,
+            timeout: Option<PendingId>,
+            cancelled: Option<Action>,
+        }
+
+        fn reducer(
+            state: &mut State,
+            action: &ActionWithMeta<Action>,
+            queue: &mut Dispatcher<Action, State>,
+        ) {
+            state.log.push(*action.action());
+            match action.action() {
+                Action::Request => state.timeout = Some(queue.push(Action::Timeout)),
+                Action::Response => {
+                    let timeout = state.timeout.take().unwrap();
+                    state.cancelled = queue.cancel(timeout);
+                    // Already cancelled.
+                    assert_eq!(queue.cancel(timeout), None);
+                }
+                Action::Timeout => {}
+            }
+        }
+
+        fn effects(store: &mut Store<State, VirtualClock, Action>, action: ActionWithMeta<Action>) {
+            if let Action::Request = action.action() {
+                store.dispatch(Action::Response);
+            }
+        }
+
+        for mode in [DispatchMode::DepthFirst, DispatchMode::BreadthFirst] {
+            let mut store = Store::builder(reducer, effects, VirtualClock::new(), State::default())
+                .dispatch_mode(mode)
+                .build()
+                .unwrap();
+            store.dispatch(Action::Request);
+            assert_eq!(
+                store.state().log,
+                [Action::Request, Action::Response],
+                "{mode:?}"
+            );
+            assert_eq!(store.state().cancelled, Some(Action::Timeout), "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn multiple_callback() {
+        use crate::callback;
+
+        /// Actions greater than 10 are disabled.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        struct Action(u32);
+
+        impl EnablingCondition<Vec<u32>> for Action {
+            fn is_enabled(&self, _: &Vec<u32>, _: crate::Timestamp) -> bool {
+                self.0 <= 10
+            }
+        }
+
+        impl From<u32> for Action {
+            fn from(n: u32) -> Self {
+                Self(n)
+            }
+        }
+
+        impl From<crate::AnyAction> for Action {
+            fn from(action: crate::AnyAction) -> Self {
+                *action.0.downcast().unwrap()
+            }
+        }
+
+        /// Action 1 queues 3, 20 and 2.
+        fn reducer(
+            state: &mut Vec<u32>,
+            action: &ActionWithMeta<Action>,
+            queue: &mut Dispatcher<Action, Vec<u32>>,
+        ) {
+            state.push(action.action().0);
+            if action.action().0 == 1 {
+                queue.push_multiple_callback(
+                    callback!(queued(n: u32) -> [Action] { [n + 2, 20, n + 1] }),
+                    1,
+                );
+            }
+        }
+
+        fn effects(_: &mut Store<Vec<u32>, VirtualClock, Action>, _: ActionWithMeta<Action>) {}
+
+        let mut store = Store::builder(reducer, effects, VirtualClock::new(), Vec::new())
+            .build()
+            .unwrap();
+        let numbers = callback!(numbers(numbers: Vec<u32>) -> [Action] { numbers });
+        assert!(store.dispatch_multiple_callback(numbers.clone(), vec![5, 11, 1, 12]));
+        assert_eq!(store.state(), &[5, 1, 3, 2]);
+        assert!(!store.dispatch_multiple_callback(numbers, vec![11, 12]));
+        assert_eq!(store.state(), &[5, 1, 3, 2]);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn serialize_state_with_own_time_base() {
+        use crate::test_utils::{restart, RESTART_DELAY, STARTED_AT};
+
+        type State = Instant;
+        fn reducer(_: &mut State, _: &ActionWithMeta<Action>, _: &mut Dispatcher<Action, State>) {}
+        fn effects(_: &mut Store<State, VirtualClock, Action>, _: ActionWithMeta<Action>) {}
+
+        let now = Instant::now();
+        let (store, restarted) = restart(now, || {
+            Store::builder(reducer, effects, VirtualClock::new(), now)
+        });
+
+        let mut json = Vec::new();
+        store
+            .serialize_state(&mut serde_json::Serializer::new(&mut json))
+            .unwrap();
+        assert_eq!(json, STARTED_AT.as_nanos().to_string().as_bytes());
+
+        let state = restarted
+            .deserialize_state(&mut serde_json::Deserializer::from_slice(&json))
+            .unwrap();
+        assert_eq!(state, now - RESTART_DELAY);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn restore_with_own_time_base() {
+        use crate::test_utils::{restart, RESTART_DELAY, STARTED_AT};
+
+        type State = Instant;
+        fn reducer(
+            state: &mut State,
+            _: &ActionWithMeta<Action>,
+            _: &mut Dispatcher<Action, State>,
+        ) {
+            *state += Duration::from_secs(1);
+        }
+        fn effects(_: &mut Store<State, VirtualClock, Action>, _: ActionWithMeta<Action>) {}
+
+        let now = Instant::now();
+        let (mut store, mut restarted) = restart(now, || {
+            Store::builder(reducer, effects, VirtualClock::new(), now)
+        });
+        store.dispatch(Action::Start);
+
+        let json = serde_json::to_vec(&store.snapshot()).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        let state = STARTED_AT + Duration::from_secs(1);
+        assert_eq!(value["state"], state.as_nanos() as u64);
+
+        restarted
+            .restore_from(&mut serde_json::Deserializer::from_slice(&json))
+            .unwrap();
+        assert_eq!(restarted.state(), &(*store.state() - RESTART_DELAY));
+        assert_eq!(restarted.last_action_id(), store.last_action_id());
+    }
+
+    /// Recovery from a snapshot and the action log written after it.
+    #[cfg(feature = "json")]
+    #[test]
+    fn replay() {
+        use std::{
+            io::{self, Cursor, Write},
+            sync::{Arc, Mutex},
+        };
+
+        use crate::{ActionLogReader, ActionLogWriter, JsonLines};
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+        struct Add(u32);
+
+        impl EnablingCondition<u32> for Add {}
+
+        /// Counts runs of effects and middlewares.
+        #[derive(Default)]
+        struct Service {
+            effects: u32,
+            middlewares: u32,
+        }
+
+        impl TimeService for Service {}
+
+        #[derive(Clone, Default)]
+        struct Log(Arc<Mutex<Vec<u8>>>);
+
+        impl Write for Log {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        /// Adding 10 or more queues `Add(1)`.
+        fn reducer(sum: &mut u32, action: &ActionWithMeta<Add>, queue: &mut Dispatcher<Add, u32>) {
+            let Add(n) = *action.action();
+            *sum += n;
+            if n >= 10 {
+                queue.push(Add(1));
+            }
+        }
+
+        fn effects(store: &mut Store<u32, Service, Add>, _: ActionWithMeta<Add>) {
+            store.service.effects += 1;
+        }
+
+        fn middleware(_: &u32, service: &mut Service, _: &ActionWithMeta<Add>) {
+            service.middlewares += 1;
+        }
+
+        let log = Log::default();
+        let mut store = Store::builder(reducer, effects, Service::default(), 0)
+            .recorder(ActionLogWriter::new(JsonLines, log.clone()))
+            .build()
+            .unwrap();
+        store.dispatch(Add(1));
+        let snapshot = serde_json::to_vec(&store.snapshot()).unwrap();
+        store.dispatch(Add(2));
+        store.dispatch(Add(10));
+        assert_eq!(store.state(), &14);
+
+        let recorded = Arc::new(Mutex::new(0));
+        let mut restored = Store::builder(reducer, effects, Service::default(), 0)
+            .middleware(middleware)
+            .recorder({
+                let recorded = recorded.clone();
+                move |_: &ActionWithMeta<Add>| *recorded.lock().unwrap() += 1
+            })
+            .build()
+            .unwrap();
+        restored
+            .restore_from(&mut serde_json::Deserializer::from_slice(&snapshot))
+            .unwrap();
+        assert_eq!(restored.state(), &1);
+
+        let log = log.0.lock().unwrap().clone();
+        let actions = ActionLogReader::new(JsonLines, Cursor::new(log))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(actions.len(), 4);
+        // The first action is in the snapshot, `Add(1)` queued by `Add(10)`
+        // is replayed from the log only.
+        assert_eq!(restored.replay(actions.clone()), 3);
+        assert_eq!(restored.state(), &14);
+        assert_eq!(restored.last_action_id(), store.last_action_id());
+        assert_eq!(restored.service.effects, 0);
+        assert_eq!(restored.service.middlewares, 0);
+        assert_eq!(*recorded.lock().unwrap(), 0);
+
+        // Already replayed.
+        assert_eq!(restored.replay(actions), 0);
+        assert_eq!(restored.state(), &14);
+
+        restored.dispatch(Add(3));
+        assert!(restored.last_action_id() > store.last_action_id());
+        assert_eq!(restored.service.effects, 1);
+        assert_eq!(*recorded.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn metrics() {
+        fn kind(action: &Action) -> &'static str {
+            match action {
+                Action::Start => "start",
+                Action::Queued(_) => "queued",
+                Action::Effect(_) => "effect",
+            }
+        }
+
+        let mut store = builder().action_kind(kind).metrics().build().unwrap();
+        store.dispatch(Action::Start);
+        let metrics = store.metrics().unwrap();
+        assert_eq!(
+            metrics.get("queued"),
+            ActionCounters {
+                dispatched: 3,
+                dropped_duplicates: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(metrics.get("effect").dispatched, 3);
+        assert_eq!(metrics.total().dropped_duplicates, 1);
+    }
+
+    #[test]
+    fn rejected_metrics() {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        enum Action {
+            Open,
+            Close,
+            /// Enabled only when open.
+            Send,
+        }
+
+        impl EnablingCondition<bool> for Action {
+            fn is_enabled(&self, open: &bool, _: crate::Timestamp) -> bool {
+                match self {
+                    Action::Open => !open,
+                    Action::Close | Action::Send => *open,
+                }
+            }
+        }
+
+        fn kind(action: &Action) -> &'static str {
+            match action {
+                Action::Open => "open",
+                Action::Close => "close",
+                Action::Send => "send",
+            }
+        }
+
+        /// `Open` queues `Send`.
+        fn reducer(
+            open: &mut bool,
+            action: &ActionWithMeta<Action>,
+            queue: &mut Dispatcher<Action, bool>,
+        ) {
+            match action.action() {
+                Action::Open => {
+                    *open = true;
+                    queue.push(Action::Send);
+                }
+                Action::Close => *open = false,
+                Action::Send => {}
+            }
         }
+
+        /// `Open` dispatches `Close`, before `Send` is dequeued.
+        fn effects(store: &mut Store<bool, VirtualClock, Action>, action: ActionWithMeta<Action>) {
+            if let Action::Open = action.action() {
+                store.dispatch(Action::Close);
+            }
+        }
+
+        for mode in [DispatchMode::DepthFirst, DispatchMode::BreadthFirst] {
+            let mut store = Store::builder(reducer, effects, VirtualClock::new(), false)
+                .action_kind(kind)
+                .dispatch_mode(mode)
+                .metrics()
+                .build()
+                .unwrap();
+            assert!(!store.dispatch(Action::Send));
+            assert!(store.dispatch(Action::Open));
+
+            let metrics = store.metrics().unwrap();
+            assert_eq!(
+                metrics.get("send"),
+                ActionCounters {
+                    rejected_at_dispatch: 1,
+                    rejected_from_queue: 1,
+                    ..Default::default()
+                },
+                "{mode:?}"
+            );
+            assert_eq!(metrics.get("open").dispatched, 1, "{mode:?}");
+            assert_eq!(metrics.get("close").dispatched, 1, "{mode:?}");
+
+            store.reset_metrics();
+            assert_eq!(store.metrics().unwrap().total(), ActionCounters::default());
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn tracing_spans() {
+        use std::{
+            fmt::Write,
+            sync::{Arc, Mutex},
+        };
+        use tracing::{
+            field::{Field, Visit},
+            span, Event, Metadata, Subscriber,
+        };
+
+        /// Captured span, with its fields formatted as `name=value`.
+        struct CapturedSpan {
+            parent: Option<usize>,
+            name: &'static str,
+            fields: String,
+        }
+
+        impl Visit for CapturedSpan {
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                if field.name() != "id" {
+                    write!(self.fields, " {}={value:?}", field.name()).unwrap();
+                }
+            }
+
+            fn record_str(&mut self, field: &Field, value: &str) {
+                write!(self.fields, " {}={value}", field.name()).unwrap();
+            }
+        }
+
+        /// Captures spans with their parents, ids are indices + 1.
+        #[derive(Clone, Default)]
+        struct Capture {
+            spans: Arc<Mutex<Vec<CapturedSpan>>>,
+            entered: Arc<Mutex<Vec<usize>>>,
+        }
+
+        impl Capture {
+            /// Renders the span tree, indenting children.
+            fn tree(&self) -> Vec<String> {
+                let spans = self.spans.lock().unwrap();
+                let depth = |mut i: usize| {
+                    let mut depth = 0;
+                    while let Some(parent) = spans[i].parent {
+                        depth += 1;
+                        i = parent;
+                    }
+                    depth
+                };
+                spans
+                    .iter()
+                    .enumerate()
+                    .map(|(i, span)| {
+                        format!("{}{}{}", "  ".repeat(depth(i)), span.name, span.fields)
+                    })
+                    .collect()
+            }
+        }
+
+        impl Subscriber for Capture {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, attrs: &span::Attributes<'_>) -> span::Id {
+                let parent = match attrs.parent() {
+                    Some(parent) => Some(parent.into_u64() as usize - 1),
+                    None if attrs.is_contextual() => self.entered.lock().unwrap().last().copied(),
+                    None => None,
+                };
+                let mut span = CapturedSpan {
+                    parent,
+                    name: attrs.metadata().name(),
+                    fields: String::new(),
+                };
+                attrs.record(&mut span);
+                let mut spans = self.spans.lock().unwrap();
+                spans.push(span);
+                span::Id::from_u64(spans.len() as u64)
+            }
+
+            fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+            fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+            fn event(&self, _: &Event<'_>) {}
+
+            fn enter(&self, span: &span::Id) {
+                self.entered
+                    .lock()
+                    .unwrap()
+                    .push(span.into_u64() as usize - 1);
+            }
+
+            fn exit(&self, _: &span::Id) {
+                self.entered.lock().unwrap().pop();
+            }
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        enum Action {
+            Start,
+            Child,
+            Disabled,
+        }
+
+        impl EnablingCondition<()> for Action {
+            fn is_enabled(&self, _: &(), _: crate::Timestamp) -> bool {
+                *self != Action::Disabled
+            }
+        }
+
+        fn kind(action: &Action) -> &'static str {
+            match action {
+                Action::Start => "start",
+                Action::Child => "child",
+                Action::Disabled => "disabled",
+            }
+        }
+
+        /// `Start` queues `Disabled`.
+        fn reducer(
+            _: &mut (),
+            action: &ActionWithMeta<Action>,
+            queue: &mut Dispatcher<Action, ()>,
+        ) {
+            if let Action::Start = action.action() {
+                queue.push(Action::Disabled);
+            }
+        }
+
+        /// `Start` dispatches `Child`.
+        fn effects(store: &mut Store<(), VirtualClock, Action>, action: ActionWithMeta<Action>) {
+            if let Action::Start = action.action() {
+                store.dispatch(Action::Child);
+            }
+        }
+
+        let spans = |mode| {
+            let capture = Capture::default();
+            tracing::subscriber::with_default(capture.clone(), || {
+                let mut store = Store::builder(reducer, effects, VirtualClock::new(), ())
+                    .action_kind(kind)
+                    .dispatch_mode(mode)
+                    .build()
+                    .unwrap();
+                store.dispatch(Action::Start);
+            });
+            capture.tree()
+        };
+
+        assert_eq!(
+            spans(DispatchMode::DepthFirst),
+            [
+                "dispatch kind=start depth=1 enabled=true",
+                "  reducer",
+                "  effects",
+                "    dispatch kind=child depth=2 enabled=true",
+                "      reducer",
+                "      effects",
+                "  dispatch kind=disabled depth=2 enabled=false",
+            ]
+        );
+        assert_eq!(
+            spans(DispatchMode::BreadthFirst),
+            [
+                "dispatch kind=start depth=1 enabled=true",
+                "  reducer",
+                "  effects",
+                "dispatch kind=child depth=2 enabled=true",
+                "  reducer",
+                "  effects",
+                "dispatch kind=disabled depth=2 enabled=false",
+            ]
+        );
+    }
+
+    #[test]
+    fn drift_action() {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        enum Action {
+            /// Increases the drift by the duration, then dispatches `Child`.
+            Start(Duration),
+            Child,
+            Drifted(Duration),
+        }
+
+        impl EnablingCondition<Vec<Action>> for Action {}
+
+        #[derive(Default)]
+        struct Service {
+            drift: Duration,
+        }
+
+        impl TimeService for Service {
+            fn drift(&mut self) -> Duration {
+                self.drift
+            }
+        }
+
+        fn reducer(
+            state: &mut Vec<Action>,
+            action: &ActionWithMeta<Action>,
+            _: &mut Dispatcher<Action, Vec<Action>>,
+        ) {
+            state.push(*action.action());
+        }
+
+        fn effects(
+            store: &mut Store<Vec<Action>, Service, Action>,
+            action: ActionWithMeta<Action>,
+        ) {
+            if let Action::Start(increase) = action.action() {
+                store.service.drift += *increase;
+                store.dispatch(Action::Child);
+            }
+        }
+
+        let ms = Duration::from_millis;
+        let mut store = Store::builder(reducer, effects, Service::default(), Vec::new())
+            .drift_action(ms(10), Action::Drifted)
+            .build()
+            .unwrap();
+        store.dispatch(Action::Start(ms(5)));
+        // Increased by 15ms, but only checked by top-level dispatches.
+        store.dispatch(Action::Start(ms(10)));
+        store.dispatch(Action::Child);
+        store.dispatch(Action::Child);
+
+        assert_eq!(
+            store.state(),
+            &[
+                Action::Start(ms(5)),
+                Action::Child,
+                Action::Start(ms(10)),
+                Action::Child,
+                Action::Drifted(ms(15)),
+                Action::Child,
+                Action::Child,
+            ]
+        );
     }
 }
@@ -0,0 +1,244 @@
+use std::time::Duration;
+
+use crate::{
+    metrics::action_type_name, ActionId, ActionIdGenerator, ActionKindFn, DispatchMode, Effects,
+    EnablingCondition, Instant, Middleware, QueueOrder, Recorder, RecursionDepth, Reducer, Store,
+    SystemTime, TimeService, WallClockIds,
+};
+
+/// Error returned by [`StoreBuilder::build`] when the store is misconfigured.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoreBuildError {
+    /// Maximum recursion depth must allow at least the top-level dispatch.
+    ZeroRecursionDepth,
+}
+
+impl std::fmt::Display for StoreBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ZeroRecursionDepth => write!(f, "max recursion depth must be at least 1"),
+        }
+    }
+}
+
+impl std::error::Error for StoreBuildError {}
+
+/// Builder for the [`Store`], for configuring its optional parts.
+///
+/// ```ignore
+/// let store = Store::builder(reducer, effects, service, State::default())
+///     .initial_time(SystemTime::now())
+///     .max_recursion_depth(128)
+///     .middleware(log_middleware)
+///     .recorder(recorder)
+///     .action_kind(Action::kind)
+///     .metrics()
+///     .build()?;
+/// ```
+pub struct StoreBuilder<State, Service, Action, Ids = WallClockIds> {
+    pub(crate) reducer: Reducer<State, Action>,
+    pub(crate) effects: Effects<State, Service, Action, Ids>,
+    pub(crate) service: Service,
+    pub(crate) initial_state: State,
+
+    pub(crate) initial_time: Option<SystemTime>,
+    pub(crate) initial_monotonic_time: Option<Instant>,
+    pub(crate) initial_action_id: Option<ActionId>,
+    pub(crate) max_recursion_depth: Option<RecursionDepth>,
+    pub(crate) middlewares: Vec<Middleware<State, Service, Action>>,
+    pub(crate) recorders: Vec<Box<dyn Recorder<Action> + Send>>,
+    pub(crate) action_kind: ActionKindFn<Action>,
+    pub(crate) metrics: bool,
+    pub(crate) queue_order: QueueOrder<Action>,
+    pub(crate) queued_before_effects: bool,
+    pub(crate) dispatch_mode: DispatchMode,
+    pub(crate) action_ids: Ids,
+    pub(crate) drift_action: Option<fn(Duration) -> Action>,
+    pub(crate) min_drift: Duration,
+}
+
+impl<State, Service, Action, Ids> StoreBuilder<State, Service, Action, Ids>
+where
+    Service: TimeService,
+    Action: EnablingCondition<State>,
+    Ids: ActionIdGenerator,
+{
+    pub fn new(
+        reducer: Reducer<State, Action>,
+        effects: Effects<State, Service, Action, Ids>,
+        service: Service,
+        initial_state: State,
+    ) -> Self
+    where
+        Ids: Default,
+    {
+        Self::with_action_ids(reducer, effects, service, initial_state, Ids::default())
+    }
+
+    /// Creates a builder of a store generating action ids with the given
+    /// strategy, e.g. a [`HybridLogicalClock`](crate::HybridLogicalClock)
+    /// so that ids of actions dispatched by different nodes can be ordered
+    /// causally.
+    pub fn with_action_ids(
+        reducer: Reducer<State, Action>,
+        effects: Effects<State, Service, Action, Ids>,
+        service: Service,
+        initial_state: State,
+        action_ids: Ids,
+    ) -> Self {
+        Self {
+            reducer,
+            effects,
+            service,
+            initial_state,
+
+            initial_time: None,
+            initial_monotonic_time: None,
+            initial_action_id: None,
+            max_recursion_depth: None,
+            middlewares: Vec::new(),
+            recorders: Vec::new(),
+            action_kind: action_type_name::<Action>,
+            metrics: false,
+            queue_order: QueueOrder::Fifo,
+            queued_before_effects: false,
+            dispatch_mode: DispatchMode::DepthFirst,
+            action_ids,
+            drift_action: None,
+            min_drift: Duration::ZERO,
+        }
+    }
+
+    /// System time at which the store is created.
+    ///
+    /// Defaults to [`SystemTime::now`].
+    pub fn initial_time(mut self, initial_time: SystemTime) -> Self {
+        self.initial_time = Some(initial_time);
+        self
+    }
+
+    /// Monotonic time corresponding to the [`StoreBuilder::initial_time`].
+    ///
+    /// Defaults to [`TimeService::monotonic_time`] of the service.
+    pub fn initial_monotonic_time(mut self, initial_monotonic_time: Instant) -> Self {
+        self.initial_monotonic_time = Some(initial_monotonic_time);
+        self
+    }
+
+    /// Id of the action preceding the first dispatched action.
+    ///
+    /// Defaults to [`ActionIdGenerator::initial_id`], the initial time
+    /// unless the strategy says otherwise.
+    pub fn initial_action_id(mut self, initial_action_id: ActionId) -> Self {
+        self.initial_action_id = Some(initial_action_id);
+        self
+    }
+
+    /// Maximum recursion depth of dispatch. Exceeding it panics.
+    ///
+    /// In [`DispatchMode::BreadthFirst`], it limits the length of chains of
+    /// actions instead, i.e. [`ActionMeta::depth`](crate::ActionMeta::depth),
+    /// even though the stack doesn't grow with them.
+    ///
+    /// Unlimited by default.
+    pub fn max_recursion_depth(mut self, max_recursion_depth: RecursionDepth) -> Self {
+        self.max_recursion_depth = Some(max_recursion_depth);
+        self
+    }
+
+    /// Adds a middleware, called for every enabled action before the reducer.
+    ///
+    /// Middlewares are called in the order they were added.
+    pub fn middleware(mut self, middleware: Middleware<State, Service, Action>) -> Self {
+        self.middlewares.push(middleware);
+        self
+    }
+
+    /// Adds a recorder, called for every enabled action before the reducer.
+    ///
+    /// Recorders are called in the order they were added.
+    pub fn recorder<R>(mut self, recorder: R) -> Self
+    where
+        R: Recorder<Action> + Send + 'static,
+    {
+        self.recorders.push(Box::new(recorder));
+        self
+    }
+
+    /// Function returning the kind of an action, used to label
+    /// [`Store::metrics`].
+    ///
+    /// Defaults to the type name of `Action`, so all actions have the same
+    /// kind. For an action enum, return the name of its variant.
+    pub fn action_kind(mut self, action_kind: ActionKindFn<Action>) -> Self {
+        self.action_kind = action_kind;
+        self
+    }
+
+    /// Enables counting of dispatched and rejected actions, per
+    /// [`StoreBuilder::action_kind`], available as [`Store::metrics`].
+    pub fn metrics(mut self) -> Self {
+        self.metrics = true;
+        self
+    }
+
+    /// Order in which actions queued by the reducer are dispatched.
+    ///
+    /// Defaults to [`QueueOrder::Fifo`].
+    pub fn queue_order(mut self, queue_order: QueueOrder<Action>) -> Self {
+        self.queue_order = queue_order;
+        self
+    }
+
+    /// Dispatch actions queued by the reducer before running the effects
+    /// of the action, instead of after them.
+    ///
+    /// Effects then observe the state after the queued actions, and the
+    /// actions they cause, were reduced.
+    pub fn queued_before_effects(mut self) -> Self {
+        self.queued_before_effects = true;
+        self
+    }
+
+    /// How actions dispatched by effects and queued by reducers are processed.
+    ///
+    /// Defaults to [`DispatchMode::DepthFirst`].
+    pub fn dispatch_mode(mut self, dispatch_mode: DispatchMode) -> Self {
+        self.dispatch_mode = dispatch_mode;
+        self
+    }
+
+    /// Strategy generating ids of dispatched actions.
+    pub fn action_ids(mut self, action_ids: Ids) -> Self {
+        self.action_ids = action_ids;
+        self
+    }
+
+    /// Action dispatched when [`TimeService::drift`], by default
+    /// [`Instant::drift`], increased by at least `min_increase`, e.g. after
+    /// the system or the browser tab woke up from suspension, with the
+    /// increase.
+    ///
+    /// System and monotonic clocks drift slightly apart even without
+    /// suspension, so `min_increase` should be at least a few milliseconds.
+    /// Drift is checked before every top-level dispatch, the action is
+    /// dispatched before the dispatched action.
+    pub fn drift_action(
+        mut self,
+        min_increase: Duration,
+        drift_action: fn(Duration) -> Action,
+    ) -> Self {
+        self.drift_action = Some(drift_action);
+        self.min_drift = min_increase;
+        self
+    }
+
+    /// Validates the configuration and creates the store.
+    pub fn build(self) -> Result<Store<State, Service, Action, Ids>, StoreBuildError> {
+        if self.max_recursion_depth == Some(0) {
+            return Err(StoreBuildError::ZeroRecursionDepth);
+        }
+
+        Ok(Store::from_builder(self))
+    }
+}
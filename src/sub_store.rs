@@ -12,8 +12,122 @@ pub trait SubStore<GlobalState, SubState> {
     where
         A: Into<Self::SubAction> + crate::EnablingCondition<SubState>;
 
-    fn dispatch_callback<T>(&mut self, callback: crate::Callback<T>, args: T) -> bool
+    /// Dispatch the action returned by the callback, either type-erased,
+    /// with `Self::SubAction: From<AnyAction>`, or created with
+    /// [`typed_callback!`](crate::typed_callback) for the sub action type.
+    fn dispatch_callback<T, A>(&mut self, callback: crate::Callback<T, A>, args: T) -> bool
     where
         T: 'static,
-        Self::SubAction: From<crate::AnyAction> + crate::EnablingCondition<SubState>;
+        A: 'static + Into<Self::SubAction>,
+        Self::SubAction: crate::EnablingCondition<SubState>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        typed_callback, ActionWithMeta, Callback, Dispatcher, EnablingCondition, Store, Timestamp,
+        VirtualClock,
+    };
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum SubAction {
+        /// Disabled for zero.
+        Add(u32),
+    }
+
+    impl EnablingCondition<Vec<u32>> for SubAction {
+        fn is_enabled(&self, _: &Vec<u32>, _: Timestamp) -> bool {
+            let SubAction::Add(n) = self;
+            *n > 0
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Action {
+        Sub(SubAction),
+    }
+
+    impl From<SubAction> for Action {
+        fn from(action: SubAction) -> Self {
+            Self::Sub(action)
+        }
+    }
+
+    impl EnablingCondition<State> for Action {
+        fn is_enabled(&self, state: &State, time: Timestamp) -> bool {
+            let Action::Sub(action) = self;
+            action.is_enabled(&state.sub, time)
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct State {
+        sub: Vec<u32>,
+    }
+
+    fn reducer(
+        state: &mut State,
+        action: &ActionWithMeta<Action>,
+        _: &mut Dispatcher<Action, State>,
+    ) {
+        let Action::Sub(SubAction::Add(n)) = action.action();
+        state.sub.push(*n);
+    }
+
+    fn effects(_: &mut Store<State, VirtualClock, Action>, _: ActionWithMeta<Action>) {}
+
+    impl SubStore<State, Vec<u32>> for Store<State, VirtualClock, Action> {
+        type SubAction = SubAction;
+        type Service = VirtualClock;
+
+        fn state(&self) -> &Vec<u32> {
+            &Store::state(self).sub
+        }
+
+        fn service(&mut self) -> &mut VirtualClock {
+            &mut self.service
+        }
+
+        fn state_and_service(&mut self) -> (&Vec<u32>, &mut VirtualClock) {
+            (&self.state.get().sub, &mut self.service)
+        }
+
+        fn dispatch<A>(&mut self, action: A) -> bool
+        where
+            A: Into<SubAction> + EnablingCondition<Vec<u32>>,
+        {
+            self.sub_dispatch::<A, Vec<u32>>(action)
+        }
+
+        fn dispatch_callback<T, A>(&mut self, callback: Callback<T, A>, args: T) -> bool
+        where
+            T: 'static,
+            A: 'static + Into<SubAction>,
+        {
+            let action: SubAction = callback.call(args);
+            SubStore::dispatch(self, action)
+        }
+    }
+
+    /// Only knows about the sub state and action.
+    fn add<S>(store: &mut S, n: u32) -> bool
+    where
+        S: SubStore<State, Vec<u32>, SubAction = SubAction>,
+    {
+        let callback: Callback<u32, SubAction> =
+            typed_callback!(add(n: u32) -> SubAction { SubAction::Add(n) });
+        store.dispatch_callback(callback, n)
+    }
+
+    #[test]
+    fn dispatch_typed_callback() {
+        let mut store = Store::builder(reducer, effects, VirtualClock::new(), State::default())
+            .build()
+            .unwrap();
+        assert!(add(&mut store, 2));
+        assert!(!add(&mut store, 0));
+        assert!(add(&mut store, 1));
+        assert_eq!(SubStore::state(&store), &[2, 1]);
+    }
 }
@@ -0,0 +1,473 @@
+//! Store wrapper for tests, recording dispatched actions and service calls.
+
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    ActionId, ActionWithMeta, Effects, EnablingCondition, Instant, Recorder, RecursionDepth,
+    Reducer, Store, StoreBuildError, StoreBuilder, TimeService, VirtualClock,
+};
+
+/// Action dispatched to the [`TestStore`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DispatchRecord<Action> {
+    pub action: Action,
+    /// Recursion depth the action was dispatched at.
+    pub depth: RecursionDepth,
+    /// Id of the action, `None` if it wasn't enabled.
+    pub id: Option<ActionId>,
+}
+
+impl<Action> DispatchRecord<Action> {
+    /// Whether the action was enabled, hence passed to reducer and effects.
+    pub fn enabled(&self) -> bool {
+        self.id.is_some()
+    }
+}
+
+type Records<Action> = Arc<Mutex<Vec<DispatchRecord<Action>>>>;
+
+struct TestRecorder<Action>(Records<Action>);
+
+impl<Action: Clone> Recorder<Action> for TestRecorder<Action> {
+    fn record(&mut self, action: &ActionWithMeta<Action>) {
+        self.0.lock().unwrap().push(DispatchRecord {
+            action: action.action().clone(),
+            depth: action.depth(),
+            id: Some(action.id()),
+        });
+    }
+
+    fn record_rejected(&mut self, action: &Action, depth: RecursionDepth) {
+        self.0.lock().unwrap().push(DispatchRecord {
+            action: action.clone(),
+            depth,
+            id: None,
+        });
+    }
+}
+
+/// Wraps a [`Store`] and records every action dispatched to it, including
+/// actions dispatched by effects and queued by reducers, so that tests can
+/// assert on the actions instead of inferring them from the state.
+///
+/// ```ignore
+/// let mut store = TestStore::new(reducer, effects, MockService::default(), State::default());
+/// store.dispatch(ConnectAction { peer });
+///
+/// store.assert_dispatched_after(
+///     |a| matches!(a, Action::Connect(_)),
+///     |a| matches!(a, Action::Connected(_)),
+/// );
+/// store.assert_no_rejected();
+/// store.assert_called(|call| matches!(call, Call::Dial(_)));
+/// ```
+pub struct TestStore<State, Service, Action> {
+    store: Store<State, Service, Action>,
+    records: Records<Action>,
+}
+
+impl<State, Service, Action> TestStore<State, Service, Action>
+where
+    Service: TimeService,
+    Action: EnablingCondition<State> + Clone + Debug + Send + 'static,
+{
+    pub fn new(
+        reducer: Reducer<State, Action>,
+        effects: Effects<State, Service, Action>,
+        service: Service,
+        initial_state: State,
+    ) -> Self {
+        Self::from_builder(Store::builder(reducer, effects, service, initial_state))
+            .expect("default store configuration is valid")
+    }
+
+    /// Builds the store, adding a recorder to it.
+    pub fn from_builder(
+        builder: StoreBuilder<State, Service, Action>,
+    ) -> Result<Self, StoreBuildError> {
+        let records = Records::default();
+        let store = builder.recorder(TestRecorder(records.clone())).build()?;
+        Ok(Self { store, records })
+    }
+
+    pub fn store(&self) -> &Store<State, Service, Action> {
+        &self.store
+    }
+
+    pub fn store_mut(&mut self) -> &mut Store<State, Service, Action> {
+        &mut self.store
+    }
+
+    pub fn state(&self) -> &State {
+        self.store.state()
+    }
+
+    pub fn service(&mut self) -> &mut Service {
+        &mut self.store.service
+    }
+
+    /// See [`Store::dispatch`].
+    pub fn dispatch<T>(&mut self, action: T) -> bool
+    where
+        T: Into<Action> + EnablingCondition<State>,
+    {
+        self.store.dispatch(action)
+    }
+
+    /// All actions dispatched since the store was created, or since
+    /// [`TestStore::clear_records`], in the order they were dispatched.
+    pub fn records(&self) -> Vec<DispatchRecord<Action>> {
+        self.records.lock().unwrap().clone()
+    }
+
+    /// Enabled actions, in the order they were dispatched.
+    pub fn dispatched(&self) -> Vec<Action> {
+        self.filtered(true)
+    }
+
+    /// Actions which weren't enabled, in the order they were dispatched.
+    pub fn rejected(&self) -> Vec<Action> {
+        self.filtered(false)
+    }
+
+    pub fn clear_records(&mut self) {
+        self.records.lock().unwrap().clear();
+    }
+
+    fn filtered(&self, enabled: bool) -> Vec<Action> {
+        self.records
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|record| record.enabled() == enabled)
+            .map(|record| record.action.clone())
+            .collect()
+    }
+
+    fn position(&self, after: usize, f: impl Fn(&Action) -> bool) -> Option<usize> {
+        self.records
+            .lock()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .skip(after)
+            .find(|(_, record)| record.enabled() && f(&record.action))
+            .map(|(i, _)| i)
+    }
+
+    /// Asserts that an enabled action matching `f` was dispatched.
+    #[track_caller]
+    pub fn assert_dispatched(&self, f: impl Fn(&Action) -> bool) {
+        if self.position(0, f).is_none() {
+            panic!(
+                "no matching action was dispatched, dispatched: {:#?}",
+                self.dispatched()
+            );
+        }
+    }
+
+    /// Asserts that no enabled action matching `f` was dispatched.
+    #[track_caller]
+    pub fn assert_not_dispatched(&self, f: impl Fn(&Action) -> bool) {
+        if let Some(i) = self.position(0, f) {
+            panic!("matching action was dispatched: {:?}", self.records()[i]);
+        }
+    }
+
+    /// Asserts that an enabled action matching `then` was dispatched after
+    /// an enabled action matching `first`.
+    #[track_caller]
+    pub fn assert_dispatched_after(
+        &self,
+        first: impl Fn(&Action) -> bool,
+        then: impl Fn(&Action) -> bool,
+    ) {
+        let Some(i) = self.position(0, first) else {
+            panic!(
+                "no action matching `first` was dispatched, dispatched: {:#?}",
+                self.dispatched()
+            );
+        };
+        if self.position(i + 1, then).is_none() {
+            let first = self.records()[i].action.clone();
+            panic!(
+                "no action matching `then` was dispatched after {first:?}, dispatched: {:#?}",
+                self.dispatched()
+            );
+        }
+    }
+
+    /// Asserts that all dispatched actions were enabled.
+    #[track_caller]
+    pub fn assert_no_rejected(&self) {
+        let rejected = self.rejected();
+        if !rejected.is_empty() {
+            panic!("actions were rejected: {rejected:#?}");
+        }
+    }
+}
+
+impl<State, Call, Action> TestStore<State, MockService<Call>, Action>
+where
+    Call: Debug,
+    Action: EnablingCondition<State> + Clone + Debug + Send + 'static,
+{
+    /// Service calls recorded by the [`MockService`].
+    pub fn calls(&self) -> &[Call] {
+        self.store.service.calls()
+    }
+
+    /// Asserts that a call matching `f` was recorded by the [`MockService`].
+    #[track_caller]
+    pub fn assert_called(&self, f: impl Fn(&Call) -> bool) {
+        if !self.calls().iter().any(f) {
+            panic!("no matching service call, calls: {:#?}", self.calls());
+        }
+    }
+
+    /// Asserts that no calls were recorded by the [`MockService`].
+    #[track_caller]
+    pub fn assert_no_calls(&self) {
+        if !self.calls().is_empty() {
+            panic!("unexpected service calls: {:#?}", self.calls());
+        }
+    }
+}
+
+/// Service recording calls made to it, for use with [`TestStore`].
+///
+/// Service traits of the state machine are implemented for
+/// `MockService<Call>`, recording each call as `Call`:
+///
+/// ```ignore
+/// impl P2pService for MockService<Call> {
+///     fn dial(&mut self, peer: PeerId) {
+///         self.record(Call::Dial(peer));
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockService<Call> {
+    clock: VirtualClock,
+    calls: Vec<Call>,
+}
+
+impl<Call> Default for MockService<Call> {
+    fn default() -> Self {
+        Self::new(VirtualClock::new())
+    }
+}
+
+impl<Call> MockService<Call> {
+    pub fn new(clock: VirtualClock) -> Self {
+        Self {
+            clock,
+            calls: Vec::new(),
+        }
+    }
+
+    pub fn clock(&self) -> &VirtualClock {
+        &self.clock
+    }
+
+    pub fn record(&mut self, call: Call) {
+        self.calls.push(call);
+    }
+
+    pub fn calls(&self) -> &[Call] {
+        &self.calls
+    }
+
+    /// Takes the recorded calls, clearing them.
+    pub fn take_calls(&mut self) -> Vec<Call> {
+        std::mem::take(&mut self.calls)
+    }
+}
+
+impl<Call> TimeService for MockService<Call> {
+    fn monotonic_time(&mut self) -> Instant {
+        self.clock.now()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Dispatcher, Timestamp};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Action {
+        Connect(u32),
+        Connected(u32),
+        /// Never enabled.
+        Disabled,
+    }
+
+    impl EnablingCondition<State> for Action {
+        fn is_enabled(&self, _: &State, _: Timestamp) -> bool {
+            !matches!(self, Action::Disabled)
+        }
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Call {
+        Dial(u32),
+    }
+
+    /// Connected peers.
+    type State = Vec<u32>;
+
+    /// `Connect` queues `Connected`.
+    fn reducer(
+        state: &mut State,
+        action: &ActionWithMeta<Action>,
+        queue: &mut Dispatcher<Action, State>,
+    ) {
+        match action.action() {
+            Action::Connect(peer) => {
+                queue.push(Action::Connected(*peer));
+            }
+            Action::Connected(peer) => state.push(*peer),
+            Action::Disabled => {}
+        }
+    }
+
+    /// `Connect` dials the peer and dispatches `Disabled`.
+    fn effects(
+        store: &mut Store<State, MockService<Call>, Action>,
+        action: ActionWithMeta<Action>,
+    ) {
+        if let Action::Connect(peer) = action.action() {
+            store.service.record(Call::Dial(*peer));
+            store.dispatch(Action::Disabled);
+        }
+    }
+
+    fn connected() -> TestStore<State, MockService<Call>, Action> {
+        let mut store = TestStore::new(reducer, effects, MockService::default(), State::new());
+        store.dispatch(Action::Connect(1));
+        store
+    }
+
+    #[test]
+    fn records() {
+        let store = connected();
+        let records = store.records();
+        assert_eq!(
+            records
+                .iter()
+                .map(|record| (record.action.clone(), record.depth, record.enabled()))
+                .collect::<Vec<_>>(),
+            [
+                (Action::Connect(1), 1, true),
+                (Action::Disabled, 2, false),
+                (Action::Connected(1), 2, true),
+            ]
+        );
+        assert!(records[0].id < records[2].id);
+        assert_eq!(records[2].id, Some(store.store().last_action_id()));
+
+        assert_eq!(
+            store.dispatched(),
+            [Action::Connect(1), Action::Connected(1)]
+        );
+        assert_eq!(store.rejected(), [Action::Disabled]);
+        assert_eq!(store.state(), &[1]);
+    }
+
+    #[test]
+    fn rejected_at_top_level() {
+        let mut store = connected();
+        store.clear_records();
+        assert!(!store.dispatch(Action::Disabled));
+        assert_eq!(
+            store.records(),
+            [DispatchRecord {
+                action: Action::Disabled,
+                depth: 1,
+                id: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn assertions() {
+        let store = connected();
+        store.assert_dispatched(|action| matches!(action, Action::Connected(1)));
+        store.assert_not_dispatched(|action| matches!(action, Action::Disabled));
+        store.assert_dispatched_after(
+            |action| matches!(action, Action::Connect(_)),
+            |action| matches!(action, Action::Connected(_)),
+        );
+        store.assert_called(|call| matches!(call, Call::Dial(1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "no matching action was dispatched")]
+    fn assert_dispatched() {
+        connected().assert_dispatched(|action| matches!(action, Action::Connected(2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "no matching action was dispatched")]
+    fn assert_dispatched_rejected() {
+        connected().assert_dispatched(|action| matches!(action, Action::Disabled));
+    }
+
+    #[test]
+    #[should_panic(expected = "matching action was dispatched")]
+    fn assert_not_dispatched() {
+        connected().assert_not_dispatched(|action| matches!(action, Action::Connect(_)));
+    }
+
+    #[test]
+    #[should_panic(expected = "no action matching `then` was dispatched after Connected(1)")]
+    fn assert_dispatched_after_order() {
+        connected().assert_dispatched_after(
+            |action| matches!(action, Action::Connected(_)),
+            |action| matches!(action, Action::Connect(_)),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "no action matching `first` was dispatched")]
+    fn assert_dispatched_after_missing_first() {
+        connected().assert_dispatched_after(
+            |action| matches!(action, Action::Connected(2)),
+            |action| matches!(action, Action::Connect(_)),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "actions were rejected")]
+    fn assert_no_rejected() {
+        connected().assert_no_rejected();
+    }
+
+    #[test]
+    fn mock_service() {
+        let mut store = connected();
+        assert_eq!(store.calls(), [Call::Dial(1)]);
+        assert_eq!(store.service().take_calls(), [Call::Dial(1)]);
+        store.assert_no_calls();
+
+        store.dispatch(Action::Connect(2));
+        store.dispatch(Action::Connect(3));
+        assert_eq!(store.calls(), [Call::Dial(2), Call::Dial(3)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "no matching service call")]
+    fn assert_called() {
+        connected().assert_called(|call| matches!(call, Call::Dial(2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "unexpected service calls")]
+    fn assert_no_calls() {
+        connected().assert_no_calls();
+    }
+}
@@ -0,0 +1,33 @@
+//! Helpers shared by tests of multiple modules.
+
+use std::time::Duration;
+
+use crate::{EnablingCondition, Instant, Store, StoreBuilder, SystemTime, TimeService};
+
+/// System time of `now` in the first store built by [`restart`].
+pub(crate) const STARTED_AT: Duration = Duration::from_secs(100);
+
+/// How much later the second store built by [`restart`] is started, hence
+/// how much earlier an [`Instant`] serialized by the first store is when
+/// deserialized by the second.
+pub(crate) const RESTART_DELAY: Duration = Duration::from_secs(900);
+
+/// Builds the store, and the same store as if it was restarted
+/// [`RESTART_DELAY`] later, both at the monotonic time `now`.
+pub(crate) fn restart<State, Service, Action>(
+    now: Instant,
+    builder: impl Fn() -> StoreBuilder<State, Service, Action>,
+) -> (Store<State, Service, Action>, Store<State, Service, Action>)
+where
+    Service: TimeService,
+    Action: EnablingCondition<State>,
+{
+    let build = |started_at| {
+        builder()
+            .initial_time(SystemTime::UNIX_EPOCH + started_at)
+            .initial_monotonic_time(now)
+            .build()
+            .unwrap()
+    };
+    (build(STARTED_AT), build(STARTED_AT + RESTART_DELAY))
+}
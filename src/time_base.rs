@@ -0,0 +1,143 @@
+use std::{cell::Cell, sync::OnceLock};
+
+use crate::{Instant, SystemTime, Timestamp};
+
+/// Time base used by global time conversions, e.g. [`Timestamp::global_now`].
+///
+/// Initialized by the first created [`Store`](crate::Store), or lazily
+/// to the current time if time is converted before any store exists.
+static GLOBAL: OnceLock<TimeBase> = OnceLock::new();
+
+thread_local! {
+    /// Time base set by [`TimeBase::scope`], overriding the global one.
+    static SCOPED: Cell<Option<TimeBase>> = const { Cell::new(None) };
+}
+
+/// Monotonic and system time reference points.
+///
+/// Used to convert monotonic time ([`Instant`]) to system time
+/// ([`Timestamp`]). Each [`Store`](crate::Store) owns its time base,
+/// so stores created with different initial times don't affect each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeBase {
+    monotonic: Instant,
+    time: Timestamp,
+}
+
+impl TimeBase {
+    /// Creates a time base where `monotonic` corresponds to `system` time.
+    pub fn new(monotonic: Instant, system: SystemTime) -> Self {
+        let nanos = system
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|x| x.as_nanos())
+            .unwrap_or(0);
+        Self {
+            monotonic,
+            time: Timestamp::new(nanos as u64),
+        }
+    }
+
+    /// Creates a time base for the current time.
+    pub fn now() -> Self {
+        Self::new(Instant::now(), SystemTime::now())
+    }
+
+    /// Time base used by global time conversions.
+    pub fn global() -> Self {
+        *GLOBAL.get_or_init(Self::now)
+    }
+
+    /// Time base set by [`TimeBase::scope`] on the current thread, or the
+    /// global one.
+    pub fn current() -> Self {
+        SCOPED.get().unwrap_or_else(Self::global)
+    }
+
+    /// Runs `f` with this time base as [`TimeBase::current`] on the
+    /// current thread.
+    ///
+    /// [`Instant`] is serialized as the system time it corresponds to in
+    /// the current time base, so that it can be deserialized against
+    /// another time base, e.g. after a restart:
+    ///
+    /// ```ignore
+    /// let json = store.time_base().scope(|| serde_json::to_string(store.state()))?;
+    /// // In a new process.
+    /// let state: State = store.time_base().scope(|| serde_json::from_str(&json))?;
+    /// ```
+    pub fn scope<R>(self, f: impl FnOnce() -> R) -> R {
+        struct Restore(Option<TimeBase>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                SCOPED.set(self.0);
+            }
+        }
+
+        let _restore = Restore(SCOPED.replace(Some(self)));
+        f()
+    }
+
+    /// Sets the global time base, unless it's already set.
+    pub(crate) fn init_global(self) {
+        let _ = GLOBAL.set(self);
+    }
+
+    /// Monotonic reference point.
+    #[inline(always)]
+    pub fn monotonic(&self) -> Instant {
+        self.monotonic
+    }
+
+    /// System time reference point.
+    #[inline(always)]
+    pub fn time(&self) -> Timestamp {
+        self.time
+    }
+
+    /// Converts monotonic time to system time.
+    ///
+    /// Monotonic time before the reference point is converted to the
+    /// system time reference point.
+    pub fn to_time(&self, monotonic: Instant) -> Timestamp {
+        self.time
+            .saturating_add(monotonic.saturating_duration_since(self.monotonic))
+    }
+
+    /// Converts system time to monotonic time, `None` if it can't be
+    /// represented as [`Instant`].
+    ///
+    /// Unlike [`TimeBase::to_time`], system time before the reference point
+    /// is converted to monotonic time before it.
+    pub fn to_monotonic(&self, time: Timestamp) -> Option<Instant> {
+        match time.checked_sub(self.time) {
+            Some(after) => self.monotonic.checked_add(after),
+            None => self.monotonic.checked_sub(self.time - time),
+        }
+    }
+
+    /// Converts monotonic time to system time, including monotonic time
+    /// before the reference point, saturating at the bounds of [`Timestamp`].
+    pub(crate) fn time_of(&self, monotonic: Instant) -> Timestamp {
+        match monotonic.checked_duration_since(self.monotonic) {
+            Some(after) => self.time.saturating_add(after),
+            None => self
+                .time
+                .saturating_sub(self.monotonic.duration_since(monotonic)),
+        }
+    }
+
+    /// Current system time, according to this time base.
+    pub fn now_time(&self) -> Timestamp {
+        self.to_time(Instant::now())
+    }
+}
+
+/// Converts monotonic time to nanoseconds since Unix epoch, using
+/// the global [`TimeBase`].
+///
+/// If `None` passed, returns result for current time.
+pub fn monotonic_to_time(time: Option<Instant>) -> u64 {
+    let base = TimeBase::global();
+    base.to_time(time.unwrap_or_else(Instant::now)).into()
+}
@@ -38,13 +38,113 @@ impl Timestamp {
         Self::new(crate::monotonic_to_time(None))
     }
 
+    /// Timestamp of the given duration since `UNIX_EPOCH`, `None` if it
+    /// doesn't fit.
+    pub fn from_duration_since_epoch(duration: Duration) -> Option<Self> {
+        u64::try_from(duration.as_nanos()).ok().map(Self)
+    }
+
+    /// Duration since `UNIX_EPOCH`.
+    pub fn duration_since_epoch(self) -> Duration {
+        Duration::from_nanos(self.0)
+    }
+
     pub fn checked_sub(self, rhs: Timestamp) -> Option<Duration> {
         self.0.checked_sub(rhs.0).map(Duration::from_nanos)
     }
 
+    /// Duration since `earlier`, zero if `earlier` is later.
+    pub fn saturating_duration_since(self, earlier: Timestamp) -> Duration {
+        self.checked_sub(earlier).unwrap_or_default()
+    }
+
+    /// Adds the duration, saturating at the maximum timestamp.
+    ///
+    /// ```
+    /// use redux::Timestamp;
+    /// use std::time::Duration;
+    ///
+    /// let max = Timestamp::new(u64::MAX);
+    /// assert_eq!(max.saturating_add(Duration::from_nanos(1)), max);
+    /// assert_eq!(Timestamp::ZERO.saturating_add(Duration::MAX), max);
+    /// ```
+    pub fn saturating_add(self, duration: Duration) -> Timestamp {
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        Timestamp(self.0.saturating_add(nanos))
+    }
+
+    /// Subtracts the duration, saturating at `UNIX_EPOCH`.
+    pub fn saturating_sub(self, duration: Duration) -> Timestamp {
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        Timestamp(self.0.saturating_sub(nanos))
+    }
+
     pub fn checked_add(self, other: u64) -> Option<Timestamp> {
         self.0.checked_add(other).map(Timestamp)
     }
+
+    /// Adds the duration, `None` on overflow.
+    ///
+    /// ```
+    /// use redux::Timestamp;
+    /// use std::time::Duration;
+    ///
+    /// // 2554-07-21T23:34:33.709551615Z, 584 years after `UNIX_EPOCH`.
+    /// let max = Timestamp::new(u64::MAX);
+    /// let t = max - Duration::from_secs(1);
+    ///
+    /// assert_eq!(t.checked_add_duration(Duration::from_secs(1)), Some(max));
+    /// assert_eq!(t.checked_add_duration(Duration::from_secs(2)), None);
+    /// assert_eq!(Timestamp::ZERO.checked_add_duration(Duration::MAX), None);
+    /// ```
+    pub fn checked_add_duration(self, duration: Duration) -> Option<Timestamp> {
+        u64::try_from(duration.as_nanos())
+            .ok()
+            .and_then(|nanos| self.checked_add(nanos))
+    }
+
+    /// Subtracts the duration, `None` if the result is before `UNIX_EPOCH`.
+    ///
+    /// ```
+    /// use redux::Timestamp;
+    /// use std::time::Duration;
+    ///
+    /// let t = Timestamp::new(1_000_000_000);
+    /// assert_eq!(t.checked_sub_duration(Duration::from_secs(1)), Some(Timestamp::ZERO));
+    /// assert_eq!(t.checked_sub_duration(Duration::from_nanos(1_000_000_001)), None);
+    /// ```
+    pub fn checked_sub_duration(self, duration: Duration) -> Option<Timestamp> {
+        u64::try_from(duration.as_nanos())
+            .ok()
+            .and_then(|nanos| self.0.checked_sub(nanos))
+            .map(Timestamp)
+    }
+
+    /// Adds the duration, wrapping around at the 584 year boundary.
+    ///
+    /// ```
+    /// use redux::Timestamp;
+    /// use std::time::Duration;
+    ///
+    /// let max = Timestamp::new(u64::MAX);
+    /// assert_eq!(max.wrapping_add(Duration::from_nanos(2)), Timestamp::new(1));
+    /// ```
+    pub fn wrapping_add(self, duration: Duration) -> Timestamp {
+        Timestamp(self.0.wrapping_add(duration.as_nanos() as u64))
+    }
+
+    /// Subtracts the duration, wrapping around at `UNIX_EPOCH`.
+    ///
+    /// ```
+    /// use redux::Timestamp;
+    /// use std::time::Duration;
+    ///
+    /// let t = Timestamp::ZERO.wrapping_sub(Duration::from_nanos(1));
+    /// assert_eq!(t, Timestamp::new(u64::MAX));
+    /// ```
+    pub fn wrapping_sub(self, duration: Duration) -> Timestamp {
+        Timestamp(self.0.wrapping_sub(duration.as_nanos() as u64))
+    }
 }
 
 impl From<Timestamp> for u64 {
@@ -53,6 +153,20 @@ impl From<Timestamp> for u64 {
     }
 }
 
+impl From<Timestamp> for Duration {
+    fn from(t: Timestamp) -> Self {
+        t.duration_since_epoch()
+    }
+}
+
+impl TryFrom<Duration> for Timestamp {
+    type Error = TimestampOutOfRange;
+
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        Self::from_duration_since_epoch(duration).ok_or(TimestampOutOfRange)
+    }
+}
+
 impl From<Timestamp> for SystemTime {
     fn from(value: Timestamp) -> Self {
         Self::UNIX_EPOCH + Duration::from_nanos(value.into())
@@ -61,24 +175,442 @@ impl From<Timestamp> for SystemTime {
 
 impl std::ops::Add for Timestamp {
     type Output = Timestamp;
+
+    /// Panics on overflow.
     #[inline]
     fn add(self, other: Timestamp) -> Timestamp {
-        Timestamp(self.0 + other.0)
+        self + other.0
     }
 }
 
 impl std::ops::Add<u64> for Timestamp {
     type Output = Timestamp;
+
+    /// Panics on overflow.
     #[inline]
     fn add(self, other: u64) -> Timestamp {
-        Timestamp(self.0 + other)
+        self.checked_add(other)
+            .expect("overflow when adding to timestamp")
     }
 }
 
 impl std::ops::Add<Duration> for Timestamp {
     type Output = Timestamp;
+
+    /// Panics on overflow.
+    ///
+    /// ```should_panic
+    /// use redux::Timestamp;
+    /// use std::time::Duration;
+    ///
+    /// let _ = Timestamp::new(u64::MAX) + Duration::from_nanos(1);
+    /// ```
     #[inline]
     fn add(self, other: Duration) -> Timestamp {
-        Timestamp(self.0 + other.as_nanos() as u64)
+        self.checked_add_duration(other)
+            .expect("overflow when adding duration to timestamp")
+    }
+}
+
+impl std::ops::Sub for Timestamp {
+    type Output = Duration;
+
+    /// Panics if `other` is later than `self`.
+    #[inline]
+    fn sub(self, other: Timestamp) -> Duration {
+        self.checked_sub(other)
+            .expect("overflow when subtracting timestamps")
+    }
+}
+
+impl std::ops::Sub<Duration> for Timestamp {
+    type Output = Timestamp;
+
+    /// Panics if the result is before `UNIX_EPOCH`.
+    #[inline]
+    fn sub(self, other: Duration) -> Timestamp {
+        self.checked_sub_duration(other)
+            .expect("overflow when subtracting duration from timestamp")
+    }
+}
+
+/// Formats the timestamp as RFC 3339 in UTC, with nanoseconds, e.g.
+/// `2024-02-29T13:45:01.000000123Z`.
+///
+/// ```
+/// use redux::Timestamp;
+///
+/// let t = Timestamp::new(1_709_214_301_000_000_123);
+/// assert_eq!(t.to_string(), "2024-02-29T13:45:01.000000123Z");
+/// assert_eq!(t.to_string().parse::<Timestamp>(), Ok(t));
+/// ```
+impl std::fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let secs = self.0 / 1_000_000_000;
+        let nanos = self.0 % 1_000_000_000;
+        let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+        let secs_of_day = secs % 86_400;
+        write!(
+            f,
+            "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{nanos:09}Z",
+            secs_of_day / 3600,
+            secs_of_day / 60 % 60,
+            secs_of_day % 60,
+        )
+    }
+}
+
+/// Parses an RFC 3339 timestamp, e.g. `2024-02-29T13:45:01.5+01:00`.
+///
+/// Fractions of a second beyond nanoseconds are truncated. Leap seconds
+/// are not supported.
+impl std::str::FromStr for Timestamp {
+    type Err = ParseTimestampError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use ParseTimestampError::{Format, OutOfRange};
+
+        fn number(
+            s: &str,
+            range: std::ops::RangeInclusive<i64>,
+        ) -> Result<i64, ParseTimestampError> {
+            if !s.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(Format);
+            }
+            let n = s.parse().map_err(|_| Format)?;
+            if range.contains(&n) {
+                Ok(n)
+            } else {
+                Err(Format)
+            }
+        }
+
+        // Checked so that slicing by byte offsets can't split a character.
+        if !s.is_ascii() {
+            return Err(Format);
+        }
+        let b = s.as_bytes();
+        if b.len() < 20
+            || b[4] != b'-'
+            || b[7] != b'-'
+            || !matches!(b[10], b'T' | b't' | b' ')
+            || b[13] != b':'
+            || b[16] != b':'
+        {
+            return Err(Format);
+        }
+        let year = number(&s[0..4], 0..=9999)?;
+        let month = number(&s[5..7], 1..=12)?;
+        let day = number(&s[8..10], 1..=days_in_month(year, month))?;
+        let hour = number(&s[11..13], 0..=23)?;
+        let minute = number(&s[14..16], 0..=59)?;
+        let second = number(&s[17..19], 0..=59)?;
+
+        let mut rest = &s[19..];
+        let mut nanos = 0;
+        if let Some(fraction) = rest.strip_prefix('.') {
+            let len = fraction
+                .bytes()
+                .position(|b| !b.is_ascii_digit())
+                .unwrap_or(fraction.len());
+            if len == 0 {
+                return Err(Format);
+            }
+            let digits = &fraction[..len.min(9)];
+            nanos =
+                digits.parse::<i64>().map_err(|_| Format)? * 10_i64.pow(9 - digits.len() as u32);
+            rest = &fraction[len..];
+        }
+
+        let offset = match rest.as_bytes() {
+            [b'Z' | b'z'] => 0,
+            [sign @ (b'+' | b'-'), _, _, b':', _, _] => {
+                let offset =
+                    number(&rest[1..3], 0..=23)? * 3600 + number(&rest[4..6], 0..=59)? * 60;
+                if *sign == b'+' {
+                    offset
+                } else {
+                    -offset
+                }
+            }
+            _ => return Err(Format),
+        };
+
+        let secs = days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second
+            - offset;
+        u64::try_from(secs)
+            .ok()
+            .and_then(|secs| secs.checked_mul(1_000_000_000))
+            .and_then(|secs| secs.checked_add(nanos as u64))
+            .map(Timestamp)
+            .ok_or(OutOfRange)
+    }
+}
+
+/// Error returned when converting a time before `UNIX_EPOCH`, or more
+/// than 584 years after it, to a [`Timestamp`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimestampOutOfRange;
+
+impl std::fmt::Display for TimestampOutOfRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "time out of range of timestamp")
+    }
+}
+
+impl std::error::Error for TimestampOutOfRange {}
+
+/// Error returned when parsing a [`Timestamp`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseTimestampError {
+    /// Not an RFC 3339 timestamp.
+    Format,
+    /// Valid RFC 3339 timestamp, but out of range of [`Timestamp`].
+    OutOfRange,
+}
+
+impl std::fmt::Display for ParseTimestampError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Format => write!(f, "invalid RFC 3339 timestamp"),
+            Self::OutOfRange => TimestampOutOfRange.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ParseTimestampError {}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since `UNIX_EPOCH` of the date in the proleptic Gregorian calendar.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Date in the proleptic Gregorian calendar of the days since `UNIX_EPOCH`.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(feature = "chrono")]
+impl From<Timestamp> for chrono::DateTime<chrono::Utc> {
+    fn from(t: Timestamp) -> Self {
+        let secs = t.0 / 1_000_000_000;
+        let nanos = t.0 % 1_000_000_000;
+        chrono::DateTime::from_timestamp(secs as i64, nanos as u32)
+            .expect("timestamp is within range of `DateTime`")
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::DateTime<chrono::Utc>> for Timestamp {
+    type Error = TimestampOutOfRange;
+
+    fn try_from(t: chrono::DateTime<chrono::Utc>) -> Result<Self, Self::Error> {
+        let nanos =
+            i128::from(t.timestamp()) * 1_000_000_000 + i128::from(t.timestamp_subsec_nanos());
+        u64::try_from(nanos)
+            .map(Timestamp)
+            .map_err(|_| TimestampOutOfRange)
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<Timestamp> for time::OffsetDateTime {
+    fn from(t: Timestamp) -> Self {
+        time::OffsetDateTime::from_unix_timestamp_nanos(t.0.into())
+            .expect("timestamp is within range of `OffsetDateTime`")
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<time::OffsetDateTime> for Timestamp {
+    type Error = TimestampOutOfRange;
+
+    fn try_from(t: time::OffsetDateTime) -> Result<Self, Self::Error> {
+        u64::try_from(t.unix_timestamp_nanos())
+            .map(Timestamp)
+            .map_err(|_| TimestampOutOfRange)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<Timestamp, ParseTimestampError> {
+        s.parse()
+    }
+
+    #[test]
+    fn parse_malformed() {
+        for s in [
+            "",
+            "2024-02-29",
+            "2024-02-29T13:45:01",
+            "2024-02-29T13:45:0éZ",
+            "2024-02-29T13:45:01é",
+            "é024-02-29T13:45:01Z",
+            "2024/02/29T13:45:01Z",
+            "2024-02-29X13:45:01Z",
+            "2024-02-29T13-45-01Z",
+            "2024-13-01T00:00:00Z",
+            "2024-00-01T00:00:00Z",
+            "2024-04-31T00:00:00Z",
+            "2024-02-29T24:00:00Z",
+            "2024-02-29T13:60:00Z",
+            "2024-02-29T13:45:60Z",
+            "2024-02-29T13:45:+1Z",
+            "2024-02-29T13:45:01.Z",
+            "2024-02-29T13:45:01.5",
+            "2024-02-29T13:45:01+01",
+            "2024-02-29T13:45:01+0100",
+            "2024-02-29T13:45:01+24:00",
+            "2024-02-29T13:45:01+01:60",
+            "2024-02-29T13:45:01ZZ",
+        ] {
+            assert_eq!(parse(s), Err(ParseTimestampError::Format), "{s:?}");
+        }
+    }
+
+    #[test]
+    fn parse_offsets() {
+        let utc = parse("2024-02-29T13:45:01Z").unwrap();
+        assert_eq!(utc, Timestamp::new(1_709_214_301_000_000_000));
+        assert_eq!(parse("2024-02-29t13:45:01z"), Ok(utc));
+        assert_eq!(parse("2024-02-29 13:45:01Z"), Ok(utc));
+        assert_eq!(parse("2024-02-29T13:45:01+00:00"), Ok(utc));
+        assert_eq!(parse("2024-02-29T14:45:01+01:00"), Ok(utc));
+        assert_eq!(parse("2024-02-29T08:15:01-05:30"), Ok(utc));
+        assert_eq!(parse("2024-03-01T00:45:01+11:00"), Ok(utc));
+    }
+
+    #[test]
+    fn parse_fractions() {
+        let secs = 1_709_214_301_000_000_000;
+        assert_eq!(
+            parse("2024-02-29T13:45:01.5Z"),
+            Ok(Timestamp::new(secs + 500_000_000))
+        );
+        assert_eq!(
+            parse("2024-02-29T13:45:01.000000123Z"),
+            Ok(Timestamp::new(secs + 123))
+        );
+        // Truncated to nanoseconds.
+        assert_eq!(
+            parse("2024-02-29T13:45:01.123456789999Z"),
+            Ok(Timestamp::new(secs + 123_456_789))
+        );
+        assert_eq!(
+            parse("2024-02-29T14:45:01.25+01:00"),
+            Ok(Timestamp::new(secs + 250_000_000))
+        );
+    }
+
+    #[test]
+    fn parse_leap_days() {
+        assert!(parse("2024-02-29T00:00:00Z").is_ok());
+        assert!(parse("2000-02-29T00:00:00Z").is_ok());
+        assert_eq!(
+            parse("2023-02-29T00:00:00Z"),
+            Err(ParseTimestampError::Format)
+        );
+        assert_eq!(
+            parse("2100-02-29T00:00:00Z"),
+            Err(ParseTimestampError::Format)
+        );
+        assert_eq!(
+            parse("2024-02-29T00:00:00Z").unwrap() + Duration::from_secs(86_400),
+            parse("2024-03-01T00:00:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_out_of_range() {
+        assert_eq!(parse("1970-01-01T00:00:00Z"), Ok(Timestamp::ZERO));
+        assert_eq!(
+            parse("1969-12-31T23:59:59.999999999Z"),
+            Err(ParseTimestampError::OutOfRange)
+        );
+        assert_eq!(
+            parse("1970-01-01T00:00:00+00:01"),
+            Err(ParseTimestampError::OutOfRange)
+        );
+        assert_eq!(
+            parse("2554-07-21T23:34:33.709551616Z"),
+            Err(ParseTimestampError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn display_round_trip() {
+        for t in [
+            Timestamp::ZERO,
+            Timestamp::new(1),
+            Timestamp::new(1_709_214_301_000_000_123),
+            Timestamp::new(u64::MAX),
+        ] {
+            assert_eq!(parse(&t.to_string()), Ok(t));
+        }
+        assert_eq!(
+            Timestamp::ZERO.to_string(),
+            "1970-01-01T00:00:00.000000000Z"
+        );
+        assert_eq!(
+            Timestamp::new(u64::MAX).to_string(),
+            "2554-07-21T23:34:33.709551615Z"
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_round_trip() {
+        for t in [
+            Timestamp::ZERO,
+            Timestamp::new(1_709_214_301_000_000_123),
+            Timestamp::new(u64::MAX),
+        ] {
+            let date_time = chrono::DateTime::<chrono::Utc>::from(t);
+            let duration = t.duration_since_epoch();
+            assert_eq!(date_time.timestamp(), duration.as_secs() as i64);
+            assert_eq!(date_time.timestamp_subsec_nanos(), duration.subsec_nanos());
+            assert_eq!(Timestamp::try_from(date_time), Ok(t));
+        }
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_round_trip() {
+        for t in [Timestamp::ZERO, Timestamp::new(u64::MAX)] {
+            let date_time = time::OffsetDateTime::from(t);
+            assert_eq!(date_time.unix_timestamp_nanos(), i128::from(u64::from(t)));
+            assert_eq!(Timestamp::try_from(date_time), Ok(t));
+        }
     }
 }
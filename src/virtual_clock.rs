@@ -0,0 +1,65 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use crate::{Instant, TimeService};
+
+/// Manually advanced monotonic clock.
+///
+/// Clones share the same time, so the clock can be given to multiple
+/// services and advanced from outside, e.g. by a test or a simulator.
+/// Time only moves when [`VirtualClock::advance`] is called, which makes
+/// action ids and timeouts deterministic.
+#[derive(Debug, Clone)]
+pub struct VirtualClock {
+    base: Instant,
+    elapsed_nanos: Arc<AtomicU64>,
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VirtualClock {
+    pub fn new() -> Self {
+        Self {
+            base: Instant::now(),
+            elapsed_nanos: Default::default(),
+        }
+    }
+
+    /// Current time of the clock.
+    pub fn now(&self) -> Instant {
+        self.base + self.elapsed()
+    }
+
+    /// Time elapsed since the clock was created.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.elapsed_nanos.load(Ordering::Acquire))
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        self.elapsed_nanos
+            .fetch_add(duration.as_nanos() as u64, Ordering::AcqRel);
+    }
+
+    /// Moves the clock forward, so that [`VirtualClock::elapsed`] is
+    /// `elapsed`. Does nothing if the clock is already past it.
+    pub fn advance_to(&self, elapsed: Duration) {
+        self.elapsed_nanos
+            .fetch_max(elapsed.as_nanos() as u64, Ordering::AcqRel);
+    }
+}
+
+impl TimeService for VirtualClock {
+    fn monotonic_time(&mut self) -> Instant {
+        self.now()
+    }
+}